@@ -1,21 +1,36 @@
 use crate::{
-    config::{Composition, Config, InputView, Io, OutputView, Sort},
-    input::{List as InputList, Text as InputText},
-    output::{List as OutputList, Plot as OutputPlot, Table as OutputTable},
-    tag::Pattern,
-    utils::{BoundExt, Info, UiExt},
+    blend::Window as BlendWindow,
+    config::{
+        Calculation, Composition, Config, InputUnit, InputView, Io, Model, OutputUnit, OutputView,
+        Propagation, Sort,
+    },
+    experimental::Window as ExperimentalWindow,
+    fatty_acid::Class,
+    fit::Window as FitWindow,
+    input::{is_stereospecific, List as InputList, Text as InputText},
+    interesterification::Window as InteresterificationWindow,
+    output::{
+        Lipolysis as OutputLipolysis, List as OutputList, Plot as OutputPlot, Table as OutputTable,
+    },
+    query::{ParseError, Query},
+    tag::{Pattern, Style, Wildcard},
+    utils::{BoundExt, Info, Task, UiExt},
     Input, Output, Visitor,
 };
 use anyhow::{Context as _, Error, Result};
 use eframe::{get_value, set_value, CreationContext, Frame, Storage, APP_KEY};
 use egui::{
-    global_dark_light_mode_switch, menu, warn_if_debug_build, Align, Align2, CentralPanel, Color32,
-    ComboBox, Context, Direction, DroppedFile, FontId, Id, LayerId, Layout, Order, Pos2,
-    ScrollArea, SidePanel, TextStyle, TopBottomPanel, Window,
+    global_dark_light_mode_switch, menu,
+    text::{LayoutJob, TextFormat},
+    warn_if_debug_build, Align, Align2, Button, CentralPanel, CollapsingHeader, Color32, ComboBox,
+    Context, Direction, DragValue, DroppedFile, FontId, Grid, Id, LayerId, Layout, Order, Pos2,
+    ScrollArea, SidePanel, Stroke, TextEdit, TextStyle, TopBottomPanel, Ui, Window,
 };
 use egui_notify::Toasts;
 use serde::{Deserialize, Serialize};
-use std::{default::default, ops::Bound, str, time::Duration};
+use std::{
+    collections::BTreeSet, default::default, iter::once, mem, ops::Bound, str, time::Duration,
+};
 use toml_edit::Document;
 use tracing::error;
 
@@ -33,7 +48,208 @@ fn read(file: &DroppedFile) -> Result<String> {
 
 fn parse(content: &str) -> Result<Input> {
     let document = content.parse::<Document>()?;
-    Ok(Input::new(Visitor::visit(&document)))
+    Ok(Visitor::visit(&document))
+}
+
+/// Editor of the pattern: a wildcard per position of the input layout and the
+/// user-defined groups of the fatty acids
+fn pattern(
+    ui: &mut Ui,
+    pattern: &mut Pattern,
+    positions: usize,
+    fatty_acids: &[String],
+    name: &mut String,
+) {
+    pattern.resize(positions, None);
+    ui.horizontal(|ui| {
+        ui.label("Key:")
+            .on_hover_text("Key filter by fatty acids in sn positions");
+        let groups = pattern.groups.keys().cloned().collect::<Vec<_>>();
+        for (index, current) in pattern.iter_mut().enumerate() {
+            wildcard(ui, index, current, &groups, fatty_acids);
+        }
+    });
+    CollapsingHeader::new("Groups").show(ui, |ui| {
+        let mut removed = None;
+        Grid::new("groups").striped(true).show(ui, |ui| {
+            for (name, group) in &mut pattern.groups {
+                ui.label(format!("@{name}"));
+                for fatty_acid in fatty_acids {
+                    let mut checked = group.contains(fatty_acid);
+                    if ui.checkbox(&mut checked, fatty_acid).changed() {
+                        if checked {
+                            group.insert(fatty_acid.clone());
+                        } else {
+                            group.remove(fatty_acid);
+                        }
+                    }
+                }
+                if ui.button("🗑").on_hover_text("Remove").clicked() {
+                    removed = Some(name.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(removed) = removed {
+            pattern.groups.shift_remove(&removed);
+        }
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(name);
+            let enabled = !name.is_empty() && !pattern.groups.contains_key(name);
+            if ui
+                .add_enabled(enabled, Button::new("➕"))
+                .on_hover_text("Add the group")
+                .clicked()
+            {
+                pattern.groups.insert(mem::take(name), default());
+            }
+        });
+    });
+}
+
+/// Wildcard of the position: the fatty acids (a set if several are checked),
+/// a class or a group, optionally negated
+fn wildcard(
+    ui: &mut Ui,
+    index: usize,
+    current: &mut Option<Wildcard>,
+    groups: &[String],
+    fatty_acids: &[String],
+) {
+    let text = current
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let (mut negated, mut inner) = match current.take() {
+        Some(Wildcard::Not(wildcard)) => (true, Some(*wildcard)),
+        wildcard => (false, wildcard),
+    };
+    ComboBox::new(format!("fatty_acids_combo_box_{index}"), "")
+        .selected_text(text)
+        .show_ui(ui, |ui| {
+            ui.checkbox(&mut negated, "Not");
+            ui.separator();
+            ui.selectable_value(&mut inner, None, "None");
+            for class in [
+                Class::Saturated,
+                Class::Monounsaturated,
+                Class::Polyunsaturated,
+            ] {
+                ui.selectable_value(
+                    &mut inner,
+                    Some(Wildcard::Class(class)),
+                    class.abbreviation(),
+                )
+                .on_hover_text(class.to_string());
+            }
+            for group in groups {
+                ui.selectable_value(
+                    &mut inner,
+                    Some(Wildcard::Group(group.clone())),
+                    format!("@{group}"),
+                );
+            }
+            ui.separator();
+            let mut set = match &inner {
+                Some(Wildcard::FattyAcid(fatty_acid)) => once(fatty_acid.clone()).collect(),
+                Some(Wildcard::Set(set)) => set.clone(),
+                _ => BTreeSet::new(),
+            };
+            let mut changed = false;
+            for fatty_acid in fatty_acids {
+                let mut checked = set.contains(fatty_acid);
+                if ui.checkbox(&mut checked, fatty_acid).changed() {
+                    changed = true;
+                    if checked {
+                        set.insert(fatty_acid.clone());
+                    } else {
+                        set.remove(fatty_acid);
+                    }
+                }
+            }
+            if changed {
+                inner = match set.len() {
+                    0 => None,
+                    1 => set.pop_first().map(Wildcard::FattyAcid),
+                    _ => Some(Wildcard::Set(set)),
+                };
+            }
+        })
+        .response
+        .on_hover_text("A fatty acid, a set of the fatty acids, a class or a group");
+    *current = match inner {
+        Some(wildcard) if negated => Some(Wildcard::Not(Box::new(wildcard))),
+        wildcard => wildcard,
+    };
+}
+
+/// Query text box, the query is applied if it is parsed
+fn query(
+    ui: &mut Ui,
+    query: &mut Option<Query>,
+    text: &mut String,
+    error: &mut Option<ParseError>,
+) {
+    let span = error.as_ref().map(|error| error.span.clone());
+    let mut layouter = |ui: &Ui, text: &str, _: f32| {
+        let font_id = TextStyle::Monospace.resolve(ui.style());
+        let color = ui.visuals().text_color();
+        let mut job = LayoutJob::default();
+        // The span of the previous parse, not underlined if the edited text
+        // doesn't fit it
+        let (start, end) = match &span {
+            Some(span)
+                if span.start <= span.end
+                    && span.end <= text.len()
+                    && text.is_char_boundary(span.start)
+                    && text.is_char_boundary(span.end) =>
+            {
+                (span.start, span.end)
+            }
+            _ => (text.len(), text.len()),
+        };
+        job.append(
+            &text[..start],
+            0.0,
+            TextFormat::simple(font_id.clone(), color),
+        );
+        job.append(
+            &text[start..end],
+            0.0,
+            TextFormat {
+                font_id: font_id.clone(),
+                color: Color32::RED,
+                underline: Stroke::new(1.0_f32, Color32::RED),
+                ..default()
+            },
+        );
+        job.append(&text[end..], 0.0, TextFormat::simple(font_id, color));
+        ui.fonts().layout_job(job)
+    };
+    ui.horizontal(|ui| {
+        ui.label("Query:").on_hover_text(
+            "sn1 = P, sn2 != L, sn1 in {P, St}, sn3 = SFA, sn1 = @group, contains Ln, \
+             CN >= 52, DB < 5, ECN = 46, value > 1% joined by and, or, not and the parentheses",
+        );
+        let response = ui.add(
+            TextEdit::singleline(text)
+                .hint_text("sn2 = L and not sn1 in {P, St}")
+                .layouter(&mut layouter),
+        );
+        if response.changed() {
+            *error = None;
+            *query = None;
+            if !text.trim().is_empty() {
+                match text.parse() {
+                    Ok(parsed) => *query = Some(parsed),
+                    Err(parse_error) => *error = Some(parse_error),
+                }
+            }
+        }
+    });
+    if let Some(error) = error {
+        ui.colored_label(Color32::RED, &error.message);
+    }
 }
 
 #[derive(Default, Deserialize, Serialize)]
@@ -49,12 +265,23 @@ pub struct App {
     input_view: InputView,
     available_fatty_acids: Vec<String>,
     config: Config,
+    /// Name of the new group of the pattern
+    #[serde(skip)]
+    group: String,
+    /// Text of the query
+    query: String,
+    #[serde(skip)]
+    query_error: Option<ParseError>,
 
     views: Views,
+    windows: Windows,
     #[serde(skip)]
     toasts: Toasts,
     #[serde(skip)]
     errors: Vec<Error>,
+    /// Running calculation of the output and of the lipolysis output
+    #[serde(skip)]
+    calculation: Option<Task<Result<(Output, Output)>>>,
 }
 
 impl App {
@@ -101,9 +328,7 @@ impl App {
                 self.content = read(&self.files[0])?;
                 let input = parse(&self.content)?;
                 self.views.input.list.input = input.clone();
-                self.views.output.list.output = input.output();
-                self.views.output.plot.output = input.output();
-                self.views.output.table.output = input.output();
+                self.calculate();
                 self.available_fatty_acids = input.fatty_acids();
                 Ok::<_, Error>(())
             } {
@@ -157,6 +382,61 @@ impl App {
     //     self.available_fatty_acids = input.fatty_acids();
     // }
 
+    /// Calculate the output of the input and of the blend off the UI thread,
+    /// the running calculation is replaced
+    fn calculate(&mut self) {
+        let input = self.views.input.list.input.clone();
+        let blend = self.windows.blend.blend.clone();
+        let config = self.config.clone();
+        self.calculation = Some(Task::spawn(move || {
+            let calculate = |config: &Config| -> Result<Output> {
+                let mut output = input.output(config)?;
+                if let Some(blend) = blend.output(&input, config) {
+                    output.extend(blend);
+                }
+                Ok(output)
+            };
+            let output = calculate(&config)?;
+            // Lipolysis of the moles
+            let lipolysis = match config.output_unit {
+                OutputUnit::MolPercent => output.clone(),
+                OutputUnit::WeightPercent => calculate(&Config {
+                    output_unit: OutputUnit::MolPercent,
+                    ..config.clone()
+                })?,
+            };
+            Ok((output, lipolysis))
+        }));
+    }
+
+    /// Outputs of the finished calculation
+    fn calculated(&mut self, ctx: &Context) {
+        let task = match &self.calculation {
+            Some(task) => task,
+            None => return,
+        };
+        let outputs = match task.poll() {
+            Some(outputs) => outputs.and_then(|outputs| outputs),
+            None => {
+                ctx.request_repaint();
+                return;
+            }
+        };
+        self.calculation = None;
+        self.errors.clear();
+        let (output, lipolysis) = match outputs {
+            Ok(outputs) => outputs,
+            Err(error) => {
+                self.errors.push(error);
+                default()
+            }
+        };
+        self.views.output.lipolysis.output = lipolysis;
+        self.views.output.list.output = output.clone();
+        self.views.output.plot.output = output.clone();
+        self.views.output.table.output = output;
+    }
+
     fn bottom_panel(&mut self, ctx: &Context) {}
 
     fn central_panel(&mut self, ctx: &Context) {
@@ -173,38 +453,51 @@ impl App {
             match self.io {
                 Io::Input => match self.input_view {
                     InputView::List => {
+                        self.views.input.list.calculation = self.config.calculation;
+                        self.views.input.list.input_unit = self.config.input_unit;
+                        let input = self.views.input.list.input.clone();
                         self.views.input.list.ui(ui);
-                        let input = &self.views.input.list.input;
-                        self.views.output.list.output = input.output();
-                        self.views.output.plot.output = input.output();
-                        self.views.output.table.output = input.output();
+                        if self.views.input.list.input != input {
+                            self.calculate();
+                        }
                     }
                     InputView::Text => {
                         self.views.input.text.text = self.content.clone();
                         self.views.input.text.ui(ui);
-                        self.content = self.views.input.text.text.clone();
-                        let input = parse(&self.content).unwrap();
-                        self.views.input.list.input = input.clone();
-                        self.views.output.list.output = input.output();
-                        self.views.output.plot.output = input.output();
-                        self.views.output.table.output = input.output();
-                        self.available_fatty_acids = input.fatty_acids();
+                        if self.content != self.views.input.text.text {
+                            self.content = self.views.input.text.text.clone();
+                            let input = parse(&self.content).unwrap();
+                            self.views.input.list.input = input.clone();
+                            self.calculate();
+                            self.available_fatty_acids = input.fatty_acids();
+                        }
                     }
                 },
-                Io::Output => match self.output_view {
-                    OutputView::List => {
-                        self.views.output.list.config = self.config.clone();
-                        self.views.output.list.ui(ui);
+                Io::Output => {
+                    if self.calculation.is_some() {
+                        ui.spinner();
                     }
-                    OutputView::Plot => {
-                        self.views.output.plot.config = self.config.clone();
-                        self.views.output.plot.ui(ui);
+                    for error in &self.errors {
+                        ui.colored_label(Color32::RED, format!("{error:#}"));
                     }
-                    OutputView::Table => {
-                        self.views.output.table.config = self.config.clone();
-                        self.views.output.table.ui(ui);
+                    match self.output_view {
+                        OutputView::List => {
+                            self.views.output.list.config = self.config.clone();
+                            self.views.output.list.ui(ui);
+                        }
+                        OutputView::Lipolysis => {
+                            self.views.output.lipolysis.ui(ui);
+                        }
+                        OutputView::Plot => {
+                            self.views.output.plot.config = self.config.clone();
+                            self.views.output.plot.ui(ui);
+                        }
+                        OutputView::Table => {
+                            self.views.output.table.config = self.config.clone();
+                            self.views.output.table.ui(ui);
+                        }
                     }
-                },
+                }
             }
         });
     }
@@ -228,6 +521,10 @@ impl App {
                         });
                         if let InputView::List = self.input_view {
                             ui.checkbox(&mut self.views.input.list.edit, "Edit");
+                            if self.config.input_unit != InputUnit::MolPercent {
+                                ui.checkbox(&mut self.views.input.list.converted, "mol %")
+                                    .on_hover_text("Show the values converted to mol %");
+                            }
                             ui.horizontal(|ui| {
                                 ui.selectable_value(
                                     &mut self.views.input.list.open,
@@ -247,10 +544,141 @@ impl App {
                             ui.selectable_value(&mut self.output_view, OutputView::List, "List");
                             ui.selectable_value(&mut self.output_view, OutputView::Table, "Table");
                             ui.selectable_value(&mut self.output_view, OutputView::Plot, "Plot");
+                            ui.selectable_value(
+                                &mut self.output_view,
+                                OutputView::Lipolysis,
+                                "Lipolysis",
+                            );
+                            if ui.button("📋").on_hover_text("Copy as TSV").clicked() {
+                                let output = &self.views.output.table.output;
+                                ui.output().copied_text = output
+                                    .clone()
+                                    .configure(&self.config)
+                                    .tsv(&self.config.notation);
+                            }
                         });
                     }
                 }
                 ui.separator();
+                let (calculation, draws, input_unit, model, output_unit, propagation) = (
+                    self.config.calculation,
+                    self.config.draws,
+                    self.config.input_unit,
+                    self.config.model,
+                    self.config.output_unit,
+                    self.config.propagation,
+                );
+                ui.horizontal(|ui| {
+                    ComboBox::from_label("Input unit")
+                        .selected_text(self.config.input_unit.name())
+                        .show_ui(ui, |ui| {
+                            for input_unit in [
+                                InputUnit::MolPercent,
+                                InputUnit::FameWeightPercent,
+                                InputUnit::FaWeightPercent,
+                            ] {
+                                ui.selectable_value(
+                                    &mut self.config.input_unit,
+                                    input_unit,
+                                    input_unit.name(),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text("Input unit");
+                });
+                ui.horizontal(|ui| {
+                    ComboBox::from_label("Calculated")
+                        .selected_text(Calculation::abbreviation(&self.config.calculation))
+                        .show_ui(ui, |ui| {
+                            for calculation in [
+                                None,
+                                Some(Calculation::Sn13),
+                                Some(Calculation::Sn2),
+                                Some(Calculation::Sn123),
+                            ] {
+                                ui.selectable_value(
+                                    &mut self.config.calculation,
+                                    calculation,
+                                    Calculation::name(&calculation),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text(Calculation::name(&self.config.calculation));
+                });
+                ui.horizontal(|ui| {
+                    ComboBox::from_label("Model")
+                        .selected_text(self.config.model.abbreviation())
+                        .show_ui(ui, |ui| {
+                            for model in [
+                                Model::OneTwoThreeRandom,
+                                Model::OneThreeRandomTwoRandom,
+                                Model::VanderWal,
+                                Model::CorrectedOneThreeRandomTwoRandom,
+                            ] {
+                                ui.selectable_value(&mut self.config.model, model, model.name());
+                            }
+                        })
+                        .response
+                        .on_hover_text(self.config.model.name());
+                    let stereospecific =
+                        self.views.input.list.input.values().any(is_stereospecific);
+                    if self.config.model == Model::OneTwoThreeRandom && stereospecific {
+                        ui.colored_label(Color32::YELLOW, "⚠").on_hover_text(
+                            "The stereospecific sn-1 and sn-3 values are not used by the \
+                             1,2,3-random distribution",
+                        );
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ComboBox::from_label("Output unit")
+                        .selected_text(self.config.output_unit.name())
+                        .show_ui(ui, |ui| {
+                            for output_unit in [OutputUnit::MolPercent, OutputUnit::WeightPercent] {
+                                ui.selectable_value(
+                                    &mut self.config.output_unit,
+                                    output_unit,
+                                    output_unit.name(),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text("Output unit");
+                });
+                ui.horizontal(|ui| {
+                    ComboBox::from_label("SD")
+                        .selected_text(Propagation::abbreviation(&self.config.propagation))
+                        .show_ui(ui, |ui| {
+                            for propagation in [
+                                None,
+                                Some(Propagation::Analytical),
+                                Some(Propagation::MonteCarlo),
+                            ] {
+                                ui.selectable_value(
+                                    &mut self.config.propagation,
+                                    propagation,
+                                    Propagation::name(&propagation),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text("Standard deviation propagation");
+                    if let Some(Propagation::MonteCarlo) = self.config.propagation {
+                        // The draws are kept per acylglycerol for the grouped deviations
+                        ui.add(DragValue::new(&mut self.config.draws).clamp_range(2..=10_000))
+                            .on_hover_text("Draws");
+                    }
+                });
+                if self.config.calculation != calculation
+                    || self.config.draws != draws
+                    || self.config.input_unit != input_unit
+                    || self.config.model != model
+                    || self.config.output_unit != output_unit
+                    || self.config.propagation != propagation
+                {
+                    self.calculate();
+                }
                 ui.horizontal(|ui| {
                     ComboBox::from_label("Composition")
                         .selected_text(Composition::abbreviation(&self.config.composition))
@@ -266,6 +694,31 @@ impl App {
                                 Some(Composition::PositionalType),
                                 "Positional-Type",
                             );
+                            ui.selectable_value(
+                                &mut self.config.composition,
+                                Some(Composition::Specie),
+                                "Specie",
+                            );
+                            ui.selectable_value(
+                                &mut self.config.composition,
+                                Some(Composition::Type),
+                                "Type",
+                            );
+                            ui.selectable_value(
+                                &mut self.config.composition,
+                                Some(Composition::Sum),
+                                "Sum (CN:DB)",
+                            );
+                            ui.selectable_value(
+                                &mut self.config.composition,
+                                Some(Composition::Carbons),
+                                "Carbon number",
+                            );
+                            ui.selectable_value(
+                                &mut self.config.composition,
+                                Some(Composition::Ecn),
+                                "ECN",
+                            );
                         });
                 });
                 ui.toggle_value(&mut self.views.output.plot.stacked, "☰")
@@ -275,24 +728,20 @@ impl App {
                 ui.group(|ui| {
                     ui.heading("Filter");
                     ui.separator();
-                    ui.horizontal(|ui| {
-                        ui.label("Key:")
-                            .on_hover_text("Key filter by fatty acids in sn positions");
-                        for (index, current) in self.config.pattern.iter_mut().enumerate() {
-                            ComboBox::new(format!("fatty_acids_combo_box_{index}"), "")
-                                .selected_text(current.as_deref().unwrap_or_default())
-                                .show_ui(ui, |ui| {
-                                    ui.selectable_value(current, None, "None");
-                                    for fatty_acid in &self.available_fatty_acids {
-                                        ui.selectable_value(
-                                            current,
-                                            Some(fatty_acid.clone()),
-                                            fatty_acid,
-                                        );
-                                    }
-                                });
-                        }
-                    });
+                    pattern(
+                        ui,
+                        &mut self.config.pattern,
+                        self.views.input.list.input.layout().positions(),
+                        &self.available_fatty_acids,
+                        &mut self.group,
+                    );
+                    ui.separator();
+                    query(
+                        ui,
+                        &mut self.config.query,
+                        &mut self.query,
+                        &mut self.query_error,
+                    );
                     ui.separator();
                     ui.horizontal(|ui| {
                         ui.label("Value:")
@@ -345,6 +794,23 @@ impl App {
                             );
                         });
                 });
+                // Notation
+                ui.group(|ui| {
+                    ui.heading("Notation");
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        let notation = &mut self.config.notation;
+                        ComboBox::from_id_source("notation")
+                            .selected_text(notation.style.name())
+                            .show_ui(ui, |ui| {
+                                for style in [Style::Abbreviation, Style::Dash, Style::LipidMaps] {
+                                    ui.selectable_value(&mut notation.style, style, style.name());
+                                }
+                            });
+                        ui.checkbox(&mut notation.stereo, "sn-")
+                            .on_hover_text("Stereospecific numbering prefix");
+                    });
+                });
 
                 ui.with_layout(
                     Layout::bottom_up(Align::Center).with_cross_align(Align::LEFT),
@@ -375,12 +841,34 @@ impl App {
                 global_dark_light_mode_switch(ui);
                 ui.separator();
                 ui.toggle_value(&mut self.left_panel, "🛠 Control");
+                ui.toggle_value(&mut self.windows.fit.open, "📐 Fit");
+                ui.toggle_value(&mut self.windows.blend.open, "⚗ Blend");
+                ui.toggle_value(
+                    &mut self.windows.interesterification.open,
+                    "🔀 Interesterification",
+                );
+                ui.toggle_value(&mut self.windows.experimental.open, "🔬 Experimental");
             });
         });
     }
 
     fn windows(&mut self, ctx: &Context) {
         // self.views.input.list.window(ctx);
+        let input = &mut self.views.input.list.input;
+        self.windows.fit.window(ctx, input, &self.config);
+        let input = &self.views.input.list.input;
+        if self.windows.blend.window(ctx, input, &self.config) {
+            self.calculate();
+        }
+        let input = &self.views.input.list.input;
+        let blend = &self.windows.blend.blend;
+        self.windows
+            .interesterification
+            .window(ctx, input, blend, &self.config);
+        let table = &mut self.views.output.table;
+        self.windows
+            .experimental
+            .window(ctx, &mut table.experimental, &table.output, &self.config);
     }
 }
 
@@ -399,6 +887,7 @@ impl eframe::App for App {
         // self.bottom_panel(ctx);
         self.windows(ctx);
         self.file_drag_and_drop_ui(ctx);
+        self.calculated(ctx);
         // self.toasts.show(ctx);
     }
 }
@@ -417,7 +906,16 @@ struct InputViews {
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct OutputViews {
+    lipolysis: OutputLipolysis,
     list: OutputList,
     table: OutputTable,
     plot: OutputPlot,
 }
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct Windows {
+    blend: BlendWindow,
+    experimental: ExperimentalWindow,
+    fit: FitWindow,
+    interesterification: InteresterificationWindow,
+}
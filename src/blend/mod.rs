@@ -0,0 +1,249 @@
+pub use self::{
+    optimization::{optimize, Constraints, Target},
+    window::Window,
+};
+use crate::{
+    config::OutputUnit,
+    input::{calculate, is_stereospecific, Layout},
+    output::Value,
+    Config, Input, Output, Specie,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::iter::once;
+
+/// Blend of the species
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Blend {
+    /// Fractions of the components, normalized on calculation
+    pub components: IndexMap<Specie, f64>,
+    pub fractions: Fractions,
+}
+
+impl Blend {
+    pub fn specie() -> Specie {
+        Specie::from(vec!["Blend".to_owned()])
+    }
+
+    /// Mole fractions of the components in the input, the weight fractions
+    /// are converted by the average masses of the triacylglycerols. Returns
+    /// `None` if there are no components, any mass is unknown or the input is
+    /// not of the triacylglycerols.
+    pub fn moles(&self, input: &Input, config: &Config) -> Option<IndexMap<Specie, f64>> {
+        if input.layout() != Layout::Triacylglycerol {
+            return None;
+        }
+        let output = input.output(&molar(config)).ok()?;
+        let moles = self
+            .components
+            .iter()
+            .filter(|(specie, fraction)| input.contains_key(*specie) && **fraction > 0.0)
+            .map(|(specie, &fraction)| {
+                let moles = match self.fractions {
+                    Fractions::Weight => fraction / output.average_mass(specie)?,
+                    Fractions::Mole => fraction,
+                };
+                Some((specie.clone(), moles))
+            })
+            .collect::<Option<IndexMap<_, _>>>()?;
+        let sum = moles.values().sum::<f64>();
+        if sum == 0.0 {
+            return None;
+        }
+        Some(
+            moles
+                .into_iter()
+                .map(|(specie, moles)| (specie, moles / sum))
+                .collect(),
+        )
+    }
+
+    /// Positional fatty acid composition of the blend, mole percent: the
+    /// compositions of the components averaged by the mole fractions
+    pub fn fatty_acids(
+        &self,
+        input: &Input,
+        config: &Config,
+    ) -> Option<IndexMap<String, Vec<f64>>> {
+        let moles = self.moles(input, config)?;
+        let components = moles
+            .iter()
+            .map(|(specie, &moles)| Some((input.mole_percent(specie, config)?, moles)))
+            .collect::<Option<Vec<_>>>()?;
+        let components = components
+            .iter()
+            .map(|(fatty_acids, moles)| (fatty_acids, *moles))
+            .collect::<Vec<_>>();
+        Some(average(&components))
+    }
+
+    /// Triacylglycerol composition of the blend specie, calculated from the
+    /// positional composition of the blend as of any other specie. Returns
+    /// `None` as well if the output is in weight percent and any molar mass is
+    /// unknown.
+    pub fn output(&self, input: &Input, config: &Config) -> Option<Output> {
+        let fatty_acids = self.fatty_acids(input, config)?;
+        calculate(Self::specie(), fatty_acids, input.library(), config).ok()
+    }
+
+    /// Triacylglycerol composition of the simple mixture: the triacylglycerols
+    /// of the components averaged by the mole fractions. Returns `None` as well
+    /// if the output is in weight percent and any molar mass is unknown.
+    pub fn mixture(&self, input: &Input, config: &Config) -> Option<Output> {
+        let moles = self.moles(input, config)?;
+        let output = input.output(&molar(config)).ok()?;
+        let mut values = IndexMap::new();
+        for (specie, moles) in moles {
+            for (tags, value) in output.get(&specie).into_iter().flatten() {
+                *values.entry(tags.clone()).or_default() += Value::from(value.mean * moles);
+            }
+        }
+        let species = once((Specie::from(vec!["Mixture".to_owned()]), values)).collect();
+        let output = Output::new(species, input.library().clone()).ordered(output.fatty_acids());
+        match config.output_unit {
+            OutputUnit::MolPercent => Some(output),
+            OutputUnit::WeightPercent => output.weight(),
+        }
+    }
+}
+
+/// Fractions of the blend components
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Fractions {
+    #[default]
+    Weight,
+    Mole,
+}
+
+impl Fractions {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Weight => "wt %",
+            Self::Mole => "mol %",
+        }
+    }
+}
+
+/// Positional compositions averaged by the mole fractions, stereospecific if
+/// every composition is
+fn average(components: &[(&IndexMap<String, Vec<f64>>, f64)]) -> IndexMap<String, Vec<f64>> {
+    let count = if components
+        .iter()
+        .all(|(fatty_acids, _)| is_stereospecific(fatty_acids))
+    {
+        5
+    } else {
+        3
+    };
+    let mut average = IndexMap::<_, Vec<_>>::new();
+    for (fatty_acids, moles) in components {
+        for (fatty_acid, values) in *fatty_acids {
+            let averaged = average
+                .entry(fatty_acid.clone())
+                .or_insert_with(|| vec![0.0; count]);
+            for (averaged, value) in averaged.iter_mut().zip(values) {
+                *averaged += *moles * value;
+            }
+        }
+    }
+    average
+}
+
+/// Config of the mole percent output without the standard deviations
+fn molar(config: &Config) -> Config {
+    Config {
+        output_unit: OutputUnit::MolPercent,
+        propagation: None,
+        ..config.clone()
+    }
+}
+
+mod optimization;
+mod window;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fatty_acid::Library, Tag};
+    use std::default::default;
+
+    fn specie(name: &str) -> Specie {
+        Specie::from(vec![name.to_owned()])
+    }
+
+    /// Species of a single fatty acid
+    fn input() -> Input {
+        let species = [("A", "P"), ("B", "O")]
+            .into_iter()
+            .map(|(specie, fatty_acid)| {
+                let fatty_acids = once((fatty_acid.to_owned(), vec![100.0; 3])).collect();
+                (self::specie(specie), fatty_acids)
+            })
+            .collect();
+        Input::new(species, default(), default(), default(), default())
+    }
+
+    fn blend(fractions: Fractions) -> Blend {
+        Blend {
+            components: [(specie("A"), 50.0), (specie("B"), 50.0)]
+                .into_iter()
+                .collect(),
+            fractions,
+        }
+    }
+
+    fn value(output: &Output, specie: &Specie, tag: &str) -> f64 {
+        output[specie]
+            .iter()
+            .find(|(tags, _)| tags.iter().any(|item| item.to_string() == tag))
+            .map_or(0.0, |(_, value)| value.mean)
+    }
+
+    fn mass(tag: &str) -> f64 {
+        let fatty_acids = tag.chars().map(String::from).collect::<Vec<_>>();
+        let tag = Tag::new(Layout::Triacylglycerol, fatty_acids);
+        tag.formula(&Library::default()).unwrap().average_mass()
+    }
+
+    #[test]
+    fn moles() {
+        let input = input();
+        let config = Config::default();
+        let moles = blend(Fractions::Mole).moles(&input, &config).unwrap();
+        assert!((moles[&specie("A")] - 0.5).abs() < 1e-9);
+        // The lighter component has more moles of the same weight
+        let moles = blend(Fractions::Weight).moles(&input, &config).unwrap();
+        let expected = (1.0 / mass("PPP")) / (1.0 / mass("PPP") + 1.0 / mass("OOO"));
+        assert!((moles[&specie("A")] - expected).abs() < 1e-9);
+        assert!((moles.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn output() {
+        let input = input();
+        let config = Config::default();
+        let blend = blend(Fractions::Mole);
+        let fatty_acids = blend.fatty_acids(&input, &config).unwrap();
+        assert_eq!(fatty_acids["P"], [50.0; 3]);
+        let output = blend.output(&input, &config).unwrap();
+        let specie = Blend::specie();
+        assert!((value(&output, &specie, "PPP") - 12.5).abs() < 1e-9);
+        assert!((value(&output, &specie, "POP") - 12.5).abs() < 1e-9);
+        let sum = output[&specie]
+            .values()
+            .map(|value| value.mean)
+            .sum::<f64>();
+        assert!((sum - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mixture() {
+        let input = input();
+        let config = Config::default();
+        let mixture = blend(Fractions::Mole).mixture(&input, &config).unwrap();
+        let specie = self::specie("Mixture");
+        assert!((value(&mixture, &specie, "PPP") - 50.0).abs() < 1e-9);
+        assert!((value(&mixture, &specie, "OOO") - 50.0).abs() < 1e-9);
+        assert_eq!(value(&mixture, &specie, "POP"), 0.0);
+    }
+}
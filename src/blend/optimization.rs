@@ -0,0 +1,379 @@
+use super::{average, molar, Blend, Fractions};
+use crate::{
+    input::{calculate, Layout},
+    utils::minimize,
+    Config, Input, Output, Specie,
+};
+use indexmap::IndexMap;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{default::default, ops::Bound};
+
+/// Iterations of the bisection of the projection
+const BISECTIONS: usize = 100;
+
+/// Smallest fraction of a component, percent
+const MINIMUM: f64 = 1e-6;
+
+/// Largest number of the subsets of the components optimized one by one, more
+/// are reduced by the backward elimination
+const SUBSETS: usize = 64;
+
+/// Target of the blend optimization
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Target {
+    /// Value of the fatty acid at sn-2, mole percent
+    Sn2 { fatty_acid: String, value: f64 },
+    /// Maximal value of the triacylglycerol or the group (`SUS`), labelled as
+    /// in the output
+    Maximum(String),
+    /// Triacylglycerol profile, labelled as in the output
+    Profile(IndexMap<String, f64>),
+}
+
+impl Target {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sn2 { .. } => "sn-2",
+            Self::Maximum(_) => "Maximum",
+            Self::Profile(_) => "Profile",
+        }
+    }
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Self::Maximum(default())
+    }
+}
+
+/// Constraints of the blend optimization
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Constraints {
+    /// Minimal and maximal fractions of the candidate species, percent
+    pub bounds: IndexMap<Specie, (f64, f64)>,
+    /// Maximal number of the components
+    pub components: Option<usize>,
+}
+
+/// Optimal blend
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Optimum {
+    pub blend: Blend,
+    /// Triacylglycerol composition of the blend
+    pub output: Output,
+    /// Value of the objective: the squared deviation from the target or the
+    /// negative maximum
+    pub objective: f64,
+}
+
+/// Fractions of the candidate species closest to the target. Every subset of
+/// the number of the components, with all the species of a positive minimum,
+/// is optimized from the uniform fractions and from each species at its
+/// maximum, the best blend is kept. If there are more than [`SUBSETS`] subsets,
+/// the species of the smallest fraction are eliminated one by one from the
+/// blend of all of them instead, the blend is not guaranteed to be the best.
+/// Returns `None` if the constraints are infeasible, any molar mass is unknown
+/// or the input is not of the triacylglycerols.
+pub fn optimize(
+    target: &Target,
+    constraints: &Constraints,
+    fractions: Fractions,
+    input: &Input,
+    config: &Config,
+) -> Option<Optimum> {
+    if input.layout() != Layout::Triacylglycerol {
+        return None;
+    }
+    let species = constraints
+        .bounds
+        .keys()
+        .filter(|&specie| input.contains_key(specie))
+        .cloned()
+        .collect::<Vec<_>>();
+    let molar_output = input.output(&molar(config)).ok()?;
+    // The weight percent of the blend needs the masses of the triacylglycerols
+    input
+        .output(&Config {
+            propagation: None,
+            ..config.clone()
+        })
+        .ok()?;
+    let masses = species
+        .iter()
+        .map(|specie| match fractions {
+            Fractions::Weight => molar_output.average_mass(specie),
+            Fractions::Mole => Some(1.0),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let compositions = species
+        .iter()
+        .map(|specie| input.mole_percent(specie, config))
+        .collect::<Option<Vec<_>>>()?;
+    let bounds = species
+        .iter()
+        .map(|specie| constraints.bounds[specie])
+        .collect::<Vec<_>>();
+    let configured = Config {
+        bound: Bound::Unbounded,
+        pattern: default(),
+        query: None,
+        ..config.clone()
+    };
+    let objective = |subset: &[usize], shares: &[f64]| {
+        let sum = subset
+            .iter()
+            .zip(shares)
+            .map(|(&index, share)| share / masses[index])
+            .sum::<f64>();
+        let components = subset
+            .iter()
+            .zip(shares)
+            .map(|(&index, share)| (&compositions[index], share / masses[index] / sum))
+            .collect::<Vec<_>>();
+        let fatty_acids = average(&components);
+        let labelled = || {
+            let output = calculate(
+                Blend::specie(),
+                fatty_acids.clone(),
+                input.library(),
+                config,
+            )
+            .unwrap_or_default()
+            .configure(&configured);
+            output
+                .get(&Blend::specie())
+                .into_iter()
+                .flatten()
+                .map(|(tags, value)| {
+                    let label = config.notation.tags(tags, input.library(), false);
+                    (label, value.mean)
+                })
+                .collect::<IndexMap<_, _>>()
+        };
+        match target {
+            Target::Sn2 { fatty_acid, value } => {
+                let sn2 = fatty_acids.get(fatty_acid).map_or(0.0, |values| values[1]);
+                (sn2 - value).powi(2)
+            }
+            Target::Maximum(label) => -labelled().get(label).copied().unwrap_or_default(),
+            Target::Profile(measured) => {
+                let labelled = labelled();
+                measured
+                    .iter()
+                    .map(|(label, measured)| {
+                        let calculated = labelled.get(label).copied().unwrap_or_default();
+                        (measured - calculated).powi(2)
+                    })
+                    .sum()
+            }
+        }
+    };
+    // Fractions of the subset from the uniform fractions and from each species
+    // at its maximum, `None` if the bounds are infeasible
+    let solve = |subset: &[usize]| {
+        let lower = subset
+            .iter()
+            .map(|&index| bounds[index].0)
+            .collect::<Vec<_>>();
+        let upper = subset
+            .iter()
+            .map(|&index| bounds[index].1)
+            .collect::<Vec<_>>();
+        if lower.iter().sum::<f64>() > 100.0 || upper.iter().sum::<f64>() < 100.0 {
+            return None;
+        }
+        let projection = |shares: &mut [f64]| project(shares, &lower, &upper, 100.0);
+        let mut starts = vec![vec![100.0 / subset.len() as f64; subset.len()]];
+        for index in 0..subset.len() {
+            let mut start = lower.clone();
+            start[index] = upper[index];
+            starts.push(start);
+        }
+        starts
+            .into_iter()
+            .map(|mut start| {
+                projection(&mut start);
+                minimize(|shares| objective(subset, shares), projection, start)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    };
+    let count = constraints
+        .components
+        .unwrap_or(species.len())
+        .min(species.len());
+    let (forced, free) = (0..species.len()).partition::<Vec<_>, _>(|&index| bounds[index].0 > 0.0);
+    // The smaller subsets are the faces of the subsets of the count
+    let size = count.checked_sub(forced.len())?;
+    let optimum = if binomial(free.len(), size) <= SUBSETS {
+        free.into_iter()
+            .combinations(size)
+            .filter_map(|chosen| {
+                let subset = forced.iter().copied().chain(chosen).collect::<Vec<_>>();
+                let (shares, value) = solve(&subset)?;
+                Some((subset, shares, value))
+            })
+            .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+    } else {
+        // Backward elimination: the species of the smallest fraction removed
+        // one by one
+        let mut subset = (0..species.len()).collect::<Vec<_>>();
+        loop {
+            let (shares, value) = solve(&subset)?;
+            if subset.len() <= count {
+                break Some((subset, shares, value));
+            }
+            let (position, _) = shares
+                .iter()
+                .enumerate()
+                .filter(|&(position, _)| !forced.contains(&subset[position]))
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))?;
+            subset.remove(position);
+        }
+    };
+    let (subset, shares, objective) = optimum?;
+    let blend = Blend {
+        components: subset
+            .into_iter()
+            .zip(shares)
+            .filter(|&(_, share)| share >= MINIMUM)
+            .map(|(index, share)| (species[index].clone(), share))
+            .collect(),
+        fractions,
+    };
+    let output = blend.output(input, config)?;
+    Some(Optimum {
+        blend,
+        output,
+        objective,
+    })
+}
+
+/// Binomial coefficient, saturated
+fn binomial(n: usize, k: usize) -> usize {
+    (0..k)
+        .try_fold(1, |binomial: usize, index| {
+            Some(binomial.checked_mul(n - index)? / (index + 1))
+        })
+        .unwrap_or(usize::MAX)
+}
+
+/// Euclidean projection onto the simplex with the bounds: the values shifted
+/// by the bisected shift and clamped to the bounds sum to the sum
+fn project(values: &mut [f64], lower: &[f64], upper: &[f64], sum: f64) {
+    let clamped = |shift: f64| {
+        values
+            .iter()
+            .zip(lower.iter().zip(upper))
+            .map(|(value, (&lower, &upper))| (value - shift).clamp(lower, upper))
+            .collect::<Vec<_>>()
+    };
+    let (mut minimum, mut maximum) = values.iter().zip(lower.iter().zip(upper)).fold(
+        (f64::MAX, f64::MIN),
+        |(minimum, maximum), (value, (lower, upper))| {
+            (minimum.min(value - upper), maximum.max(value - lower))
+        },
+    );
+    for _ in 0..BISECTIONS {
+        let shift = (minimum + maximum) / 2.0;
+        if clamped(shift).iter().sum::<f64>() > sum {
+            minimum = shift;
+        } else {
+            maximum = shift;
+        }
+    }
+    let clamped = clamped((minimum + maximum) / 2.0);
+    values.copy_from_slice(&clamped);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::once;
+
+    fn specie(name: &str) -> Specie {
+        Specie::from(vec![name.to_owned()])
+    }
+
+    /// Species of a single fatty acid
+    fn input() -> Input {
+        let species = [("A", "P"), ("B", "O"), ("C", "L")]
+            .into_iter()
+            .map(|(specie, fatty_acid)| {
+                let fatty_acids = once((fatty_acid.to_owned(), vec![100.0; 3])).collect();
+                (self::specie(specie), fatty_acids)
+            })
+            .collect();
+        Input::new(species, default(), default(), default(), default())
+    }
+
+    fn constraints(components: Option<usize>) -> Constraints {
+        Constraints {
+            bounds: ["A", "B", "C"]
+                .into_iter()
+                .map(|name| (specie(name), (0.0, 100.0)))
+                .collect(),
+            components,
+        }
+    }
+
+    #[test]
+    fn sn2() {
+        let target = Target::Sn2 {
+            fatty_acid: "P".to_owned(),
+            value: 30.0,
+        };
+        let optimum = optimize(
+            &target,
+            &constraints(None),
+            Fractions::Mole,
+            &input(),
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(optimum.objective < 1e-6, "{}", optimum.objective);
+        let sum = optimum.blend.components.values().sum::<f64>();
+        assert!((sum - 100.0).abs() < 1e-6);
+        assert!((optimum.blend.components[&specie("A")] - 30.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn components() {
+        let target = Target::Sn2 {
+            fatty_acid: "P".to_owned(),
+            value: 30.0,
+        };
+        let optimum = optimize(
+            &target,
+            &constraints(Some(2)),
+            Fractions::Mole,
+            &input(),
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(optimum.blend.components.len() <= 2);
+        assert!(optimum.objective < 1e-6, "{}", optimum.objective);
+    }
+
+    #[test]
+    fn infeasible() {
+        let mut constraints = constraints(None);
+        constraints.bounds[&specie("A")] = (60.0, 100.0);
+        constraints.bounds[&specie("B")] = (60.0, 100.0);
+        let optimum = optimize(
+            &Target::default(),
+            &constraints,
+            Fractions::Mole,
+            &input(),
+            &Config::default(),
+        );
+        assert!(optimum.is_none());
+    }
+
+    #[test]
+    fn binomials() {
+        assert_eq!(binomial(5, 2), 10);
+        assert_eq!(binomial(5, 0), 1);
+        assert_eq!(binomial(usize::MAX, 3), usize::MAX);
+    }
+}
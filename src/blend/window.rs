@@ -0,0 +1,270 @@
+use super::{optimization::Optimum, optimize, Blend, Constraints, Fractions, Target};
+use crate::{fit::measured, input::Layout, output::difference, utils::Task, Config, Input};
+use egui::{
+    Button, CollapsingHeader, Color32, ComboBox, Context, DragValue, Grid, TextEdit, Ui,
+    Window as EguiWindow,
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::default::default;
+
+/// Blend window: the blend components and the comparison with the simple
+/// mixture
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Window {
+    pub open: bool,
+    pub blend: Blend,
+    /// Optimization target and constraints
+    target: Target,
+    constraints: Constraints,
+    /// Measured profile of the profile target
+    text: String,
+    /// Objective of the last optimization or the error
+    optimized: Option<Result<f64, String>>,
+    /// Running optimization
+    #[serde(skip)]
+    optimization: Option<Task<Option<Optimum>>>,
+}
+
+impl Window {
+    /// Returns `true` if the blend is changed
+    pub fn window(&mut self, ctx: &Context, input: &Input, config: &Config) -> bool {
+        let mut open = self.open;
+        let blend = self.blend.clone();
+        EguiWindow::new("⚗ Blend")
+            .open(&mut open)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                if input.layout() != Layout::Triacylglycerol {
+                    ui.colored_label(Color32::YELLOW, "⚠ Triacylglycerol input only");
+                    return;
+                }
+                ComboBox::from_label("Fractions")
+                    .selected_text(self.blend.fractions.name())
+                    .show_ui(ui, |ui| {
+                        for fractions in [Fractions::Weight, Fractions::Mole] {
+                            ui.selectable_value(
+                                &mut self.blend.fractions,
+                                fractions,
+                                fractions.name(),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text("Fractions of the components, normalized to the sum");
+                Grid::new("components").striped(true).show(ui, |ui| {
+                    for specie in input.keys() {
+                        let mut checked = self.blend.components.contains_key(specie);
+                        if ui.checkbox(&mut checked, specie.to_string()).changed() {
+                            if checked {
+                                self.blend.components.insert(specie.clone(), 0.0);
+                            } else {
+                                self.blend.components.remove(specie);
+                            }
+                        }
+                        if let Some(fraction) = self.blend.components.get_mut(specie) {
+                            ui.add(
+                                DragValue::new(fraction)
+                                    .clamp_range(0.0..=100.0)
+                                    .speed(0.1)
+                                    .suffix("%"),
+                            );
+                        }
+                        ui.end_row();
+                    }
+                });
+                let sum = self.blend.components.values().sum::<f64>();
+                ui.label(format!("∑ {sum:.2}%"));
+                CollapsingHeader::new("Optimization").show(ui, |ui| {
+                    self.optimization(ui, input, config);
+                });
+                let moles = match self.blend.moles(input, config) {
+                    Some(moles) => moles,
+                    None => {
+                        ui.colored_label(
+                            Color32::YELLOW,
+                            "⚠ No components or an unknown molar mass",
+                        );
+                        return;
+                    }
+                };
+                ui.label(format!(
+                    "Mole fractions: {}",
+                    moles
+                        .iter()
+                        .map(|(specie, moles)| format!("{specie} {:.2}%", 100.0 * moles))
+                        .join(", ")
+                ));
+                let (output, mixture) = match (
+                    self.blend.output(input, config),
+                    self.blend.mixture(input, config),
+                ) {
+                    (Some(output), Some(mixture)) => (output, mixture),
+                    _ => {
+                        ui.colored_label(
+                            Color32::YELLOW,
+                            "⚠ Unknown molar mass, the output is not converted",
+                        );
+                        return;
+                    }
+                };
+                ui.separator();
+                ui.label("Difference from the simple mixture of the triacylglycerols");
+                difference(ui, mixture, output, config);
+            });
+        self.open = open;
+        self.blend != blend
+    }
+
+    fn optimization(&mut self, ui: &mut Ui, input: &Input, config: &Config) {
+        Grid::new("constraints").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("Min");
+            ui.label("Max");
+            ui.end_row();
+            for specie in input.keys() {
+                let mut checked = self.constraints.bounds.contains_key(specie);
+                if ui.checkbox(&mut checked, specie.to_string()).changed() {
+                    if checked {
+                        self.constraints.bounds.insert(specie.clone(), (0.0, 100.0));
+                    } else {
+                        self.constraints.bounds.remove(specie);
+                    }
+                }
+                if let Some((minimum, maximum)) = self.constraints.bounds.get_mut(specie) {
+                    ui.add(
+                        DragValue::new(minimum)
+                            .clamp_range(0.0..=*maximum)
+                            .speed(0.1)
+                            .suffix("%"),
+                    );
+                    ui.add(
+                        DragValue::new(maximum)
+                            .clamp_range(*minimum..=100.0)
+                            .speed(0.1)
+                            .suffix("%"),
+                    );
+                }
+                ui.end_row();
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut limited = self.constraints.components.is_some();
+            ui.checkbox(&mut limited, "Components")
+                .on_hover_text("Maximal number of the components");
+            match (limited, &mut self.constraints.components) {
+                (true, Some(components)) => {
+                    ui.add(DragValue::new(components).clamp_range(1..=input.len().max(1)));
+                }
+                (true, components) => *components = Some(input.len().max(1)),
+                (false, components) => *components = None,
+            }
+        });
+        ui.horizontal(|ui| {
+            ComboBox::from_label("Target")
+                .selected_text(self.target.name())
+                .show_ui(ui, |ui| {
+                    let targets = [
+                        Target::Sn2 {
+                            fatty_acid: input.fatty_acids().first().cloned().unwrap_or_default(),
+                            value: 0.0,
+                        },
+                        Target::Maximum(String::new()),
+                        Target::Profile(default()),
+                    ];
+                    for target in targets {
+                        let selected = self.target.name() == target.name();
+                        if ui.selectable_label(selected, target.name()).clicked() && !selected {
+                            self.target = target;
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("Optimization target");
+            match &mut self.target {
+                Target::Sn2 { fatty_acid, value } => {
+                    ComboBox::from_id_source("fatty_acid")
+                        .selected_text(fatty_acid.as_str())
+                        .show_ui(ui, |ui| {
+                            for key in input.fatty_acids() {
+                                let text = key.clone();
+                                ui.selectable_value(fatty_acid, key, text);
+                            }
+                        });
+                    ui.add(
+                        DragValue::new(value)
+                            .clamp_range(0.0..=100.0)
+                            .speed(0.1)
+                            .suffix("%"),
+                    )
+                    .on_hover_text("sn-2, mol %");
+                }
+                Target::Maximum(label) => {
+                    ui.text_edit_singleline(label)
+                        .on_hover_text("The triacylglycerol or the group as in the output (SUS)");
+                }
+                Target::Profile(_) => {}
+            }
+        });
+        if let Target::Profile(_) = self.target {
+            ui.label("Profile:").on_hover_text(
+                "A label and a value per line, the labels as in the output (POP 12.5)",
+            );
+            ui.add(TextEdit::multiline(&mut self.text).code_editor());
+        }
+        let running = self.optimization.is_some();
+        if ui.add_enabled(!running, Button::new("Optimize")).clicked() {
+            let target = match &self.target {
+                Target::Profile(_) => measured(&self.text).map(Target::Profile),
+                target => Ok(target.clone()),
+            };
+            match target {
+                Ok(target) => {
+                    let constraints = self.constraints.clone();
+                    let fractions = self.blend.fractions;
+                    let input = input.clone();
+                    let config = config.clone();
+                    self.optimization = Some(Task::spawn(move || {
+                        optimize(&target, &constraints, fractions, &input, &config)
+                    }));
+                }
+                Err(error) => self.optimized = Some(Err(format!("{error:#}"))),
+            }
+        }
+        if let Some(optimization) = &self.optimization {
+            match optimization.poll() {
+                Some(result) => {
+                    self.optimized = Some(match result {
+                        Ok(Some(optimum)) => {
+                            self.blend = optimum.blend;
+                            Ok(optimum.objective)
+                        }
+                        Ok(None) => {
+                            Err("Infeasible constraints or an unknown molar mass".to_owned())
+                        }
+                        Err(error) => Err(format!("{error:#}")),
+                    });
+                    self.optimization = None;
+                }
+                None => {
+                    ui.spinner();
+                    ui.ctx().request_repaint();
+                    return;
+                }
+            }
+        }
+        match &self.optimized {
+            Some(Ok(objective)) => {
+                let text = match self.target {
+                    Target::Maximum(_) => format!("Maximum: {:.4}%", -objective),
+                    _ => format!("∑ deviation²: {objective:.6}"),
+                };
+                ui.label(text);
+            }
+            Some(Err(error)) => {
+                ui.colored_label(Color32::RED, error);
+            }
+            None => {}
+        }
+    }
+}
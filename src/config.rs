@@ -1,4 +1,7 @@
-use crate::tag::Pattern;
+use crate::{
+    query::Query,
+    tag::{Notation, Pattern},
+};
 use serde::{Deserialize, Serialize};
 use std::{
     default::default,
@@ -9,8 +12,17 @@ use std::{
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Config {
     pub bound: Bound<f64>,
+    pub calculation: Option<Calculation>,
     pub composition: Option<Composition>,
+    /// Number of the Monte Carlo draws
+    pub draws: usize,
+    pub input_unit: InputUnit,
+    pub model: Model,
+    pub notation: Notation,
+    pub output_unit: OutputUnit,
     pub pattern: Pattern,
+    pub propagation: Option<Propagation>,
+    pub query: Option<Query>,
     pub sort: Option<Sort>,
 }
 
@@ -18,8 +30,16 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             bound: Bound::Unbounded,
+            calculation: default(),
             composition: default(),
+            draws: 1000,
+            input_unit: default(),
+            model: default(),
+            notation: default(),
+            output_unit: default(),
             pattern: default(),
+            propagation: default(),
+            query: default(),
             sort: default(),
         }
     }
@@ -59,6 +79,7 @@ impl Display for Sort {
 pub enum OutputView {
     #[default]
     List,
+    Lipolysis,
     Plot,
     Table,
 }
@@ -67,6 +88,11 @@ pub enum OutputView {
 pub enum Composition {
     PositionalSpecie,
     PositionalType,
+    Specie,
+    Type,
+    Sum,
+    Carbons,
+    Ecn,
 }
 
 impl Composition {
@@ -75,6 +101,11 @@ impl Composition {
             None => "",
             Some(Composition::PositionalSpecie) => "Positional-specie composition",
             Some(Composition::PositionalType) => "Positional-type composition",
+            Some(Composition::Specie) => "Specie composition",
+            Some(Composition::Type) => "Type composition",
+            Some(Composition::Sum) => "Sum composition (CN:DB)",
+            Some(Composition::Carbons) => "Carbon number composition",
+            Some(Composition::Ecn) => "Equivalent carbon number composition",
         }
     }
 
@@ -83,6 +114,160 @@ impl Composition {
             None => "",
             Some(Composition::PositionalSpecie) => "PSC",
             Some(Composition::PositionalType) => "PTC",
+            Some(Composition::Specie) => "SC",
+            Some(Composition::Type) => "TC",
+            Some(Composition::Sum) => "CN:DB",
+            Some(Composition::Carbons) => "CN",
+            Some(Composition::Ecn) => "ECN",
+        }
+    }
+}
+
+/// Unit of the input values
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum InputUnit {
+    /// Mole percent of fatty acids
+    #[default]
+    MolPercent,
+    /// Weight percent of fatty acid methyl esters
+    FameWeightPercent,
+    /// Weight percent of fatty acids
+    FaWeightPercent,
+}
+
+impl InputUnit {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MolPercent => "mol %",
+            Self::FameWeightPercent => "wt % FAME",
+            Self::FaWeightPercent => "wt % FA",
+        }
+    }
+}
+
+/// Unit of the output values
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum OutputUnit {
+    /// Mole percent of triacylglycerols
+    #[default]
+    MolPercent,
+    /// Weight percent of triacylglycerols
+    WeightPercent,
+}
+
+impl OutputUnit {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MolPercent => "mol %",
+            Self::WeightPercent => "wt %",
+        }
+    }
+}
+
+/// Propagation of the input standard deviations to the triacylglycerols
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Propagation {
+    /// First-order propagation by the partial derivatives
+    Analytical,
+    /// Sampling of normally distributed input values
+    MonteCarlo,
+}
+
+impl Propagation {
+    pub fn name(propagation: &Option<Self>) -> &'static str {
+        match propagation {
+            None => "",
+            Some(Self::Analytical) => "Analytical (first-order)",
+            Some(Self::MonteCarlo) => "Monte Carlo",
+        }
+    }
+
+    pub fn abbreviation(propagation: &Option<Self>) -> &'static str {
+        match propagation {
+            None => "",
+            Some(Self::Analytical) => "A",
+            Some(Self::MonteCarlo) => "MC",
+        }
+    }
+}
+
+/// Positional column calculated from the other two measured columns
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Calculation {
+    /// sn-1,3 calculated from sn-1,2,3 and sn-2 (lipase hydrolysis)
+    Sn13,
+    /// sn-2 calculated from sn-1,2,3 and sn-1,3
+    Sn2,
+    /// sn-1,2,3 calculated from sn-1,3 and sn-2
+    Sn123,
+}
+
+impl Calculation {
+    /// Index of the calculated column
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Sn13 => 0,
+            Self::Sn2 => 1,
+            Self::Sn123 => 2,
+        }
+    }
+
+    pub fn name(calculation: &Option<Self>) -> &'static str {
+        match calculation {
+            None => "All columns measured",
+            Some(Self::Sn13) => "sn-1,3 calculated from sn-1,2,3 and sn-2 (lipase hydrolysis)",
+            Some(Self::Sn2) => "sn-2 calculated from sn-1,2,3 and sn-1,3",
+            Some(Self::Sn123) => "sn-1,2,3 calculated from sn-1,3 and sn-2",
+        }
+    }
+
+    pub fn abbreviation(calculation: &Option<Self>) -> &'static str {
+        match calculation {
+            None => "",
+            Some(Self::Sn13) => "sn-1,3",
+            Some(Self::Sn2) => "sn-2",
+            Some(Self::Sn123) => "sn-1,2,3",
+        }
+    }
+}
+
+/// TAG distribution model
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Model {
+    /// 1,2,3-random: every position takes the sn-1,2,3 composition, the
+    /// stereospecific input is not used
+    OneTwoThreeRandom,
+    /// 1,3-random-2-random: sn-1 and sn-3 take the sn-1,3 composition, sn-2 takes
+    /// the sn-2 composition. With stereospecific input sn-1 and sn-3 take their
+    /// own compositions (1-random-2-random-3-random).
+    #[default]
+    OneThreeRandomTwoRandom,
+    /// Vander Wal: sn-1,3 composition is calculated from the sn-1,2,3 and sn-2
+    /// compositions. With stereospecific input sn-1 and sn-3 differ from it by
+    /// half of the difference of their own compositions.
+    VanderWal,
+    /// Corrected 1,3-random-2-random: like Vander Wal, but negative calculated
+    /// values are set to zero and every position is normalized to 100%
+    CorrectedOneThreeRandomTwoRandom,
+}
+
+impl Model {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OneTwoThreeRandom => "1,2,3-random",
+            Self::OneThreeRandomTwoRandom => "1,3-random-2-random",
+            Self::VanderWal => "Vander Wal",
+            Self::CorrectedOneThreeRandomTwoRandom => "Corrected 1,3-random-2-random",
+        }
+    }
+
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Self::OneTwoThreeRandom => "123R",
+            Self::OneThreeRandomTwoRandom => "13R2R",
+            Self::VanderWal => "VW",
+            Self::CorrectedOneThreeRandomTwoRandom => "C13R2R",
         }
     }
 }
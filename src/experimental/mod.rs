@@ -0,0 +1,211 @@
+pub use self::window::Window;
+use crate::{tag::Group, utils::FloatExt, Config, Output, Specie, Tag, Tags};
+use indexmap::IndexMap;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, default::default, iter::once, ops::Bound};
+
+/// Experimental values of the triacylglycerols of a specie, in the output unit
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Experimental {
+    /// Specie of the compared calculated values
+    pub specie: Specie,
+    values: IndexMap<Tags, f64>,
+    /// Values of the group labels
+    groups: IndexMap<Group, f64>,
+    /// Labels without a triacylglycerol or a group in the calculated output
+    pub unmatched: Vec<String>,
+}
+
+impl Experimental {
+    /// Match the measured labels with the triacylglycerols of the specie in
+    /// the output (before configure), the labels are in any notation of the
+    /// tags. For the grouped compositions a label stands for its whole group
+    /// (`POP` for POP, PPO and OPP of the specie group) or is the group as in
+    /// the output grouped by the config (`ECN 44`, `TG 52:4`, `S2U`).
+    pub fn new(
+        specie: Specie,
+        measured: &IndexMap<String, f64>,
+        output: &Output,
+        config: &Config,
+    ) -> Self {
+        let tags = output
+            .get(&specie)
+            .into_iter()
+            .flat_map(IndexMap::keys)
+            .flatten()
+            .collect::<BTreeSet<_>>();
+        let fatty_acids = tags
+            .iter()
+            .flat_map(|tag| tag.iter())
+            .unique()
+            .cloned()
+            .collect::<Vec<_>>();
+        let layout = tags.first().map_or(default(), |tag| tag.layout());
+        let configured = output.clone().configure(&unfiltered(config));
+        let groups = configured
+            .get(&specie)
+            .into_iter()
+            .flat_map(IndexMap::keys)
+            .filter_map(|tags| {
+                let label = config.notation.tags(tags, output.library(), false);
+                Some((label, tags.group()?))
+            })
+            .collect::<IndexMap<_, _>>();
+        let mut values = IndexMap::new();
+        let mut matched_groups = IndexMap::new();
+        let mut unmatched = Vec::new();
+        for (label, &value) in measured {
+            let tag = Tag::parse(label, layout, &fatty_acids, output.library()).ok();
+            match (
+                tag.as_ref().and_then(|tag| tags.get(tag)),
+                groups.get(label),
+            ) {
+                (Some(&tag), _) => {
+                    *values
+                        .entry(once(tag.clone()).collect::<Tags>())
+                        .or_default() += value
+                }
+                (None, Some(&group)) => *matched_groups.entry(group.clone()).or_default() += value,
+                (None, None) => unmatched.push(label.clone()),
+            }
+        }
+        Self {
+            specie,
+            values,
+            groups: matched_groups,
+            unmatched,
+        }
+    }
+
+    /// Experimental values grouped as the output by the config, not bounded
+    /// and not filtered by the pattern or the query. The values of the group
+    /// labels are added to their groups if the output is grouped by them.
+    pub fn configure(&self, output: &Output, config: &Config) -> IndexMap<Tags, f64> {
+        let config = unfiltered(config);
+        let values = self
+            .values
+            .iter()
+            .map(|(tags, &value)| (tags.clone(), value.into()))
+            .collect();
+        let species = once((self.specie.clone(), values)).collect();
+        let experimental = Output::new(species, output.library().clone())
+            .ordered(output.fatty_acids())
+            .configure(&config);
+        let mut values = experimental
+            .get(&self.specie)
+            .into_iter()
+            .flatten()
+            .map(|(tags, value)| (tags.clone(), value.mean))
+            .collect::<IndexMap<_, _>>();
+        let calculated = output.clone().configure(&config);
+        let groups = calculated
+            .get(&self.specie)
+            .into_iter()
+            .flat_map(IndexMap::keys)
+            .filter_map(Tags::group)
+            .collect::<BTreeSet<_>>();
+        for (group, &value) in &self.groups {
+            if !groups.contains(group) {
+                continue;
+            }
+            match values
+                .iter_mut()
+                .find(|(tags, _)| tags.group() == Some(group))
+            {
+                Some((_, measured)) => *measured += value,
+                None => {
+                    values.insert(Tags::grouped(group.clone(), default()), value);
+                }
+            }
+        }
+        values
+    }
+
+    /// Statistics of the calculated values of the specie configured by the
+    /// config
+    pub fn statistics(&self, output: &Output, config: &Config) -> Statistics {
+        let calculated = output
+            .clone()
+            .configure(config)
+            .get(&self.specie)
+            .into_iter()
+            .flatten()
+            .map(|(tags, value)| (tags.clone(), value.mean))
+            .collect();
+        Statistics::new(&self.configure(output, config), &calculated)
+    }
+}
+
+/// Config of the output not bounded and not filtered by the pattern or the
+/// query
+fn unfiltered(config: &Config) -> Config {
+    Config {
+        bound: Bound::Unbounded,
+        pattern: default(),
+        query: None,
+        ..config.clone()
+    }
+}
+
+/// Value of the tags, the grouped tags are compared by the group: the
+/// experimental group holds only the measured tags
+pub fn get(values: &IndexMap<Tags, f64>, tags: &Tags) -> Option<f64> {
+    values
+        .iter()
+        .find(|(key, _)| match (key.group(), tags.group()) {
+            (Some(key), Some(group)) => key == group,
+            _ => *key == tags,
+        })
+        .map(|(_, &value)| value)
+}
+
+/// Goodness of the fit of the calculated values to the experimental
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Statistics {
+    pub count: usize,
+    /// Root mean square error
+    pub rmse: f64,
+    /// Pearson's chi-square, the calculated values as the expected
+    pub chi_square: f64,
+    /// Coefficient of determination, `None` if the experimental values do not
+    /// vary
+    pub r_squared: Option<f64>,
+}
+
+impl Statistics {
+    /// Statistics over the experimental values, a value not in the calculated
+    /// output (filtered by the bound or the pattern) is zero
+    pub fn new(experimental: &IndexMap<Tags, f64>, calculated: &IndexMap<Tags, f64>) -> Self {
+        let pairs = experimental
+            .iter()
+            .map(|(tags, &measured)| (measured, get(calculated, tags).unwrap_or_default()))
+            .collect::<Vec<_>>();
+        let count = pairs.len();
+        if count == 0 {
+            return default();
+        }
+        let mean = pairs.iter().map(|(measured, _)| measured).sum::<f64>() / count as f64;
+        let residuals = pairs
+            .iter()
+            .map(|(measured, calculated)| (measured - calculated).powi(2))
+            .sum::<f64>();
+        let total = pairs
+            .iter()
+            .map(|(measured, _)| (measured - mean).powi(2))
+            .sum::<f64>();
+        let chi_square = pairs
+            .iter()
+            .filter(|(_, calculated)| *calculated > 0.0)
+            .map(|(measured, calculated)| (measured - calculated).powi(2) / calculated)
+            .sum();
+        Self {
+            count,
+            rmse: (residuals / count as f64).sqrt(),
+            chi_square,
+            r_squared: (!total.is_approx_zero()).then(|| 1.0 - residuals / total),
+        }
+    }
+}
+
+mod window;
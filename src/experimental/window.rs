@@ -0,0 +1,117 @@
+use super::{Experimental, Statistics};
+use crate::{fit::measured, Config, Output, Specie};
+use egui::{Color32, ComboBox, Context, Grid, TextEdit, Ui, Window as EguiWindow};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// Experimental window: the measured values compared with the calculated
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Window {
+    pub open: bool,
+    /// Specie of the compared calculated values
+    specie: Option<Specie>,
+    text: String,
+    error: Option<String>,
+}
+
+impl Window {
+    pub fn window(
+        &mut self,
+        ctx: &Context,
+        experimental: &mut Option<Experimental>,
+        output: &Output,
+        config: &Config,
+    ) {
+        let mut open = self.open;
+        EguiWindow::new("🔬 Experimental")
+            .open(&mut open)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                ComboBox::from_label("Calculated")
+                    .selected_text(
+                        self.specie
+                            .as_ref()
+                            .map(ToString::to_string)
+                            .unwrap_or_default(),
+                    )
+                    .show_ui(ui, |ui| {
+                        for specie in output.keys() {
+                            let text = specie.to_string();
+                            ui.selectable_value(&mut self.specie, Some(specie.clone()), text);
+                        }
+                    })
+                    .response
+                    .on_hover_text("Specie of the compared calculated values");
+                ui.label("Measured:").on_hover_text(
+                    "A triacylglycerol or a group and a value per line (POP 12.5, ECN 44 20.1), \
+                     for the grouped compositions a triacylglycerol stands for its group",
+                );
+                ui.add(TextEdit::multiline(&mut self.text).code_editor());
+                ui.horizontal(|ui| {
+                    if let Some(specie) = &self.specie {
+                        if ui.button("Compare").clicked() {
+                            self.error = None;
+                            match measured(&self.text) {
+                                Ok(measured) => {
+                                    *experimental = Some(Experimental::new(
+                                        specie.clone(),
+                                        &measured,
+                                        output,
+                                        config,
+                                    ));
+                                }
+                                Err(error) => self.error = Some(format!("{error:#}")),
+                            }
+                        }
+                    }
+                    if ui.button("Clear").clicked() {
+                        *experimental = None;
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                let experimental = match experimental {
+                    Some(experimental) => experimental,
+                    None => return,
+                };
+                if !experimental.unmatched.is_empty() {
+                    ui.colored_label(
+                        Color32::YELLOW,
+                        format!(
+                            "⚠ Not calculated: {}",
+                            experimental.unmatched.iter().join(", ")
+                        ),
+                    );
+                }
+                ui.separator();
+                let statistics = experimental.statistics(output, config);
+                statistics.ui(ui);
+            });
+        self.open = open;
+    }
+}
+
+impl Statistics {
+    pub fn ui(&self, ui: &mut Ui) {
+        Grid::new("statistics").striped(true).show(ui, |ui| {
+            ui.label("n");
+            ui.label(self.count.to_string());
+            ui.end_row();
+            ui.label("RMSE");
+            ui.label(format!("{:.4}", self.rmse));
+            ui.end_row();
+            ui.label("χ²");
+            ui.label(format!("{:.4}", self.chi_square));
+            ui.end_row();
+            ui.label("R²");
+            match self.r_squared {
+                Some(r_squared) => ui.label(format!("{r_squared:.4}")),
+                None => ui
+                    .label("—")
+                    .on_hover_text("The experimental values do not vary"),
+            };
+            ui.end_row();
+        });
+    }
+}
@@ -0,0 +1,194 @@
+use super::{Class, FattyAcid, Saturation};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Built-in fatty acids
+static BUILTINS: [Builtin; 19] = [
+    Builtin::new("Cy", "8:0", "Caprylic acid", "Octanoic acid"),
+    Builtin::new("C", "10:0", "Capric acid", "Decanoic acid"),
+    Builtin::new("La", "12:0", "Lauric acid", "Dodecanoic acid"),
+    Builtin::new("M", "14:0", "Myristic acid", "Tetradecanoic acid"),
+    Builtin::new("P", "16:0", "Palmitic acid", "Hexadecanoic acid"),
+    Builtin::new("Ma", "17:0", "Margaric acid", "Heptadecanoic acid"),
+    Builtin::new("St", "18:0", "Stearic acid", "Octadecanoic acid"),
+    Builtin::new("A", "20:0", "Arachidic acid", "Icosanoic acid"),
+    Builtin::new("Be", "22:0", "Behenic acid", "Docosanoic acid"),
+    Builtin::new("Lg", "24:0", "Lignoceric acid", "Tetracosanoic acid"),
+    Builtin::new(
+        "Po",
+        "16:1Δ9c",
+        "Palmitoleic acid",
+        "(9Z)-Hexadec-9-enoic acid",
+    ),
+    Builtin::new("H", "16:1", "Hexadecenoic acid", "Hexadecenoic acid"),
+    Builtin::new("O", "18:1Δ9c", "Oleic acid", "(9Z)-Octadec-9-enoic acid"),
+    Builtin::new(
+        "L",
+        "18:2Δ9c,12c",
+        "Linoleic acid",
+        "(9Z,12Z)-Octadeca-9,12-dienoic acid",
+    ),
+    Builtin::new(
+        "Ln",
+        "18:3Δ9c,12c,15c",
+        "α-Linolenic acid",
+        "(9Z,12Z,15Z)-Octadeca-9,12,15-trienoic acid",
+    ),
+    Builtin::new("G", "20:1Δ11c", "Gondoic acid", "(11Z)-Icos-11-enoic acid"),
+    Builtin::new("E", "22:1Δ13c", "Erucic acid", "(13Z)-Docos-13-enoic acid"),
+    Builtin::new(
+        "R",
+        "18:1Δ9c;12OH",
+        "Ricinoleic acid",
+        "(9Z)-12-Hydroxyoctadec-9-enoic acid",
+    ),
+    Builtin::new(
+        "V",
+        "18:1Δ9c;12Ep",
+        "Vernolic acid",
+        "(9Z)-12,13-Epoxyoctadec-9-enoic acid",
+    ),
+];
+
+/// Fatty acid library
+///
+/// The built-in fatty acids are extended or overridden by the input entries.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Library {
+    entries: IndexMap<String, Entry>,
+}
+
+impl Library {
+    pub fn insert(&mut self, abbreviation: String, entry: Entry) {
+        self.entries.insert(abbreviation, entry);
+    }
+
+    /// Structure of the fatty acid, the key is an abbreviation of the library
+    /// or a shorthand
+    pub fn fatty_acid(&self, fatty_acid: &str) -> Option<FattyAcid> {
+        let shorthand = self
+            .entries
+            .get(fatty_acid)
+            .and_then(|entry| entry.shorthand.as_deref())
+            .or_else(|| Some(builtin(fatty_acid)?.shorthand))
+            .unwrap_or(fatty_acid);
+        shorthand.parse().ok()
+    }
+
+    /// Trivial name of the fatty acid
+    pub fn name(&self, fatty_acid: &str) -> Option<&str> {
+        self.entries
+            .get(fatty_acid)
+            .and_then(|entry| entry.name.as_deref())
+            .or_else(|| Some(builtin(fatty_acid)?.name))
+    }
+
+    /// Systematic name of the fatty acid
+    pub fn systematic_name(&self, fatty_acid: &str) -> Option<&str> {
+        self.entries
+            .get(fatty_acid)
+            .and_then(|entry| entry.systematic_name.as_deref())
+            .or_else(|| Some(builtin(fatty_acid)?.systematic_name))
+    }
+
+    /// Saturation class of the fatty acid, the input types override the
+    /// structure ones
+    pub fn saturation(&self, fatty_acid: &str) -> Option<Saturation> {
+        self.entries
+            .get(fatty_acid)
+            .and_then(|entry| entry.saturation)
+            .or_else(|| Some(self.fatty_acid(fatty_acid)?.saturation()))
+    }
+
+    /// Class of the fatty acid, the saturated by the saturation class and
+    /// the unsaturated by the double bond count
+    pub fn class(&self, fatty_acid: &str) -> Option<Class> {
+        match self.saturation(fatty_acid)? {
+            Saturation::Saturated => Some(Class::Saturated),
+            Saturation::Unsaturated => match self.fatty_acid(fatty_acid)?.bonds {
+                ..=1 => Some(Class::Monounsaturated),
+                _ => Some(Class::Polyunsaturated),
+            },
+        }
+    }
+
+    /// Description of the fatty acid: names, structure, formula and masses
+    pub fn description(&self, fatty_acid: &str) -> String {
+        let mut description = String::new();
+        if let Some(name) = self.name(fatty_acid) {
+            writeln!(description, "{name}").ok();
+        }
+        if let Some(systematic_name) = self.systematic_name(fatty_acid) {
+            writeln!(description, "{systematic_name}").ok();
+        }
+        match self.fatty_acid(fatty_acid) {
+            Some(structure) => {
+                write!(description, "{structure}").ok();
+                if let Some(formula) = structure.formula() {
+                    writeln!(description).ok();
+                    writeln!(description, "{formula}").ok();
+                    writeln!(
+                        description,
+                        "Monoisotopic mass: {:.4}",
+                        formula.monoisotopic_mass()
+                    )
+                    .ok();
+                    write!(description, "Average mass: {:.4}", formula.average_mass()).ok();
+                }
+            }
+            None => {
+                write!(description, "Unknown structure").ok();
+            }
+        }
+        description
+    }
+}
+
+/// Library entry of the input, the fields override the built-in ones
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Entry {
+    pub shorthand: Option<String>,
+    pub name: Option<String>,
+    pub systematic_name: Option<String>,
+    pub saturation: Option<Saturation>,
+}
+
+impl Entry {
+    pub fn is_empty(&self) -> bool {
+        self.shorthand.is_none()
+            && self.name.is_none()
+            && self.systematic_name.is_none()
+            && self.saturation.is_none()
+    }
+}
+
+/// Built-in fatty acid
+struct Builtin {
+    abbreviation: &'static str,
+    shorthand: &'static str,
+    name: &'static str,
+    systematic_name: &'static str,
+}
+
+impl Builtin {
+    const fn new(
+        abbreviation: &'static str,
+        shorthand: &'static str,
+        name: &'static str,
+        systematic_name: &'static str,
+    ) -> Self {
+        Self {
+            abbreviation,
+            shorthand,
+            name,
+            systematic_name,
+        }
+    }
+}
+
+fn builtin(abbreviation: &str) -> Option<&'static Builtin> {
+    BUILTINS
+        .iter()
+        .find(|builtin| builtin.abbreviation == abbreviation)
+}
@@ -0,0 +1,434 @@
+use anyhow::{bail, ensure, Error, Result};
+use itertools::Itertools;
+pub use library::{Entry, Library};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// Fatty acid structure
+///
+/// Parsed from the lipid shorthand: `[C]carbons:bonds` followed by the double
+/// bond positions from the methyl end (`n-6`, `ω-6`) or from the carboxyl end
+/// with optional cis/trans isomerism (`Δ9c,12c`, `Δ9Z,12Z`, `(9Z,12Z)`) and
+/// by the hydroxy and epoxy groups (`;12OH`, `;12Ep`). For example `18:2n-6`,
+/// `18:1Δ9c`, `C16:0` or `18:1Δ9c;12OH`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct FattyAcid {
+    pub carbons: u8,
+    pub bonds: u8,
+    /// Double bonds, empty if the positions are unknown
+    pub positions: Vec<Bond>,
+    pub substituents: Vec<Substituent>,
+}
+
+impl FattyAcid {
+    pub fn new(carbons: u8, bonds: u8) -> Self {
+        Self {
+            carbons,
+            bonds,
+            ..Default::default()
+        }
+    }
+
+    /// Position of the last double bond from the methyl end
+    pub fn omega(&self) -> Option<u8> {
+        let last = self.positions.last()?;
+        Some(self.carbons - last.position)
+    }
+
+    /// Formula of the free fatty acid, `None` if the counts are out of the
+    /// range of the formula
+    pub fn formula(&self) -> Option<Formula> {
+        let hydrogens = self.carbons.checked_sub(self.bonds)?.checked_mul(2)?;
+        let mut formula = Formula::new(self.carbons, hydrogens, 2);
+        for substituent in &self.substituents {
+            formula.oxygens = formula.oxygens.checked_add(1)?;
+            if let Substituent::Epoxy(_) = substituent {
+                formula.hydrogens = formula.hydrogens.checked_sub(2)?;
+            }
+        }
+        Some(formula)
+    }
+
+    pub fn saturation(&self) -> Saturation {
+        if self.bonds == 0 {
+            Saturation::Saturated
+        } else {
+            Saturation::Unsaturated
+        }
+    }
+}
+
+impl Display for FattyAcid {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.carbons, self.bonds)?;
+        if !self.positions.is_empty() {
+            write!(f, "Δ{}", self.positions.iter().format(","))?;
+        }
+        for substituent in &self.substituents {
+            write!(f, ";{substituent}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FattyAcid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser(s.trim());
+        parser.eat("C");
+        let carbons = parser.number()?;
+        ensure!(parser.eat(":"), "Parse fatty acid ({s}): expected `:`");
+        let bonds = parser.number()?;
+        ensure!(
+            bonds < carbons,
+            "Parse fatty acid ({s}): expected fewer double bonds than carbons"
+        );
+        let mut positions = Vec::new();
+        if parser.eat("n-") || parser.eat("ω-") || parser.eat("n") || parser.eat("ω") {
+            let omega = parser.number()?;
+            ensure!(bonds != 0, "Parse fatty acid ({s}): omega of saturated");
+            // Widened, the methylene-interrupted span overflows `u8`
+            ensure!(
+                3 * (u16::from(bonds) - 1) + u16::from(omega) < u16::from(carbons),
+                "Parse fatty acid ({s}): omega out of chain"
+            );
+            // Methylene-interrupted double bonds
+            let last = carbons - omega;
+            for index in (0..bonds).rev() {
+                positions.push(Bond::new(last - 3 * index, None));
+            }
+        } else if parser.eat("Δ") || parser.eat("D") || parser.0.starts_with('(') {
+            let parenthesized = parser.eat("(");
+            loop {
+                let position = parser.number()?;
+                let isomerism = if parser.eat("c") || parser.eat("Z") {
+                    Some(Isomerism::Cis)
+                } else if parser.eat("t") || parser.eat("E") {
+                    Some(Isomerism::Trans)
+                } else {
+                    None
+                };
+                positions.push(Bond::new(position, isomerism));
+                if !parser.eat(",") {
+                    break;
+                }
+            }
+            ensure!(
+                !parenthesized || parser.eat(")"),
+                "Parse fatty acid ({s}): expected `)`"
+            );
+            // Ordered from the carboxyl end, the duplicates are not counted
+            positions.sort_by_key(|bond| bond.position);
+            positions.dedup_by_key(|bond| bond.position);
+            ensure!(
+                positions.len() == bonds as usize,
+                "Parse fatty acid ({s}): expected {bonds} double bond positions"
+            );
+        }
+        let mut substituents = Vec::new();
+        while parser.eat(";") {
+            let position = parser.number()?;
+            if parser.eat("OH") {
+                substituents.push(Substituent::Hydroxy(position));
+            } else if parser.eat("Ep") {
+                substituents.push(Substituent::Epoxy(position));
+            } else {
+                bail!("Parse fatty acid ({s}): expected `OH` or `Ep`");
+            }
+        }
+        ensure!(
+            parser.0.is_empty(),
+            "Parse fatty acid ({s}): unexpected `{}`",
+            parser.0
+        );
+        for position in positions
+            .iter()
+            .map(|bond| bond.position)
+            .chain(substituents.iter().map(Substituent::position))
+        {
+            ensure!(
+                0 < position && position < carbons,
+                "Parse fatty acid ({s}): position {position} out of chain"
+            );
+        }
+        let fatty_acid = Self {
+            carbons,
+            bonds,
+            positions,
+            substituents,
+        };
+        ensure!(
+            fatty_acid.formula().is_some(),
+            "Parse fatty acid ({s}): formula out of range"
+        );
+        Ok(fatty_acid)
+    }
+}
+
+/// Molecular formula
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Formula {
+    pub carbons: u8,
+    pub hydrogens: u8,
+    pub oxygens: u8,
+}
+
+impl Formula {
+    pub fn new(carbons: u8, hydrogens: u8, oxygens: u8) -> Self {
+        Self {
+            carbons,
+            hydrogens,
+            oxygens,
+        }
+    }
+
+    pub fn monoisotopic_mass(&self) -> f64 {
+        self.mass(12.0, 1.007_825_032, 15.994_914_620)
+    }
+
+    pub fn average_mass(&self) -> f64 {
+        self.mass(12.0107, 1.00794, 15.9994)
+    }
+
+    fn mass(&self, carbon: f64, hydrogen: f64, oxygen: f64) -> f64 {
+        self.carbons as f64 * carbon
+            + self.hydrogens as f64 * hydrogen
+            + self.oxygens as f64 * oxygen
+    }
+}
+
+impl Display for Formula {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "C{}H{}O{}", self.carbons, self.hydrogens, self.oxygens)
+    }
+}
+
+/// Double bond
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Bond {
+    /// Position from the carboxyl end (Δ)
+    pub position: u8,
+    pub isomerism: Option<Isomerism>,
+}
+
+impl Bond {
+    pub fn new(position: u8, isomerism: Option<Isomerism>) -> Self {
+        Self {
+            position,
+            isomerism,
+        }
+    }
+}
+
+impl Display for Bond {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.position)?;
+        match self.isomerism {
+            Some(Isomerism::Cis) => write!(f, "c"),
+            Some(Isomerism::Trans) => write!(f, "t"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Cis/trans isomerism
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Isomerism {
+    Cis,
+    Trans,
+}
+
+/// Substituent
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Substituent {
+    Hydroxy(u8),
+    /// Epoxy between the position and the next carbon
+    Epoxy(u8),
+}
+
+impl Substituent {
+    pub fn position(&self) -> u8 {
+        match *self {
+            Self::Hydroxy(position) | Self::Epoxy(position) => position,
+        }
+    }
+}
+
+impl Display for Substituent {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Hydroxy(position) => write!(f, "{position}OH"),
+            Self::Epoxy(position) => write!(f, "{position}Ep"),
+        }
+    }
+}
+
+/// Saturation
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Saturation {
+    Saturated,
+    Unsaturated,
+}
+
+impl Saturation {
+    pub fn abbreviation(&self) -> char {
+        match self {
+            Self::Saturated => 'S',
+            Self::Unsaturated => 'U',
+        }
+    }
+}
+
+impl Display for Saturation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Saturated => write!(f, "Saturated"),
+            Self::Unsaturated => write!(f, "Unsaturated"),
+        }
+    }
+}
+
+impl FromStr for Saturation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match &*s.to_lowercase() {
+            "s" | "saturated" => Ok(Self::Saturated),
+            "u" | "unsaturated" => Ok(Self::Unsaturated),
+            _ => bail!("Parse saturation ({s})"),
+        }
+    }
+}
+
+/// Class of the fatty acid by the double bond count
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Class {
+    Saturated,
+    Monounsaturated,
+    Polyunsaturated,
+}
+
+impl Class {
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Self::Saturated => "SFA",
+            Self::Monounsaturated => "MUFA",
+            Self::Polyunsaturated => "PUFA",
+        }
+    }
+}
+
+impl Display for Class {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Saturated => write!(f, "Saturated"),
+            Self::Monounsaturated => write!(f, "Monounsaturated"),
+            Self::Polyunsaturated => write!(f, "Polyunsaturated"),
+        }
+    }
+}
+
+/// Shorthand parser
+struct Parser<'a>(&'a str);
+
+impl Parser<'_> {
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.0.strip_prefix(prefix) {
+            Some(rest) => {
+                self.0 = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn number(&mut self) -> Result<u8> {
+        let end = self
+            .0
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.0.len());
+        ensure!(end != 0, "Parse number ({})", self.0);
+        let (number, rest) = self.0.split_at(end);
+        self.0 = rest;
+        Ok(number.parse()?)
+    }
+}
+
+mod library;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<FattyAcid> {
+        s.parse()
+    }
+
+    fn positions(fatty_acid: &FattyAcid) -> Vec<u8> {
+        fatty_acid
+            .positions
+            .iter()
+            .map(|bond| bond.position)
+            .collect()
+    }
+
+    #[test]
+    fn omega() {
+        let linoleic = parse("18:2n-6").unwrap();
+        assert_eq!((linoleic.carbons, linoleic.bonds), (18, 2));
+        assert_eq!(positions(&linoleic), [9, 12]);
+        assert_eq!(linoleic.omega(), Some(6));
+        assert_eq!(positions(&parse("18:3ω-3").unwrap()), [9, 12, 15]);
+    }
+
+    #[test]
+    fn delta() {
+        let oleic = parse("18:1Δ9c").unwrap();
+        assert_eq!(oleic.positions, [Bond::new(9, Some(Isomerism::Cis))]);
+        assert_eq!(oleic.to_string(), "18:1Δ9c");
+        let elaidic = parse("18:1(9E)").unwrap();
+        assert_eq!(elaidic.positions, [Bond::new(9, Some(Isomerism::Trans))]);
+        assert_eq!(positions(&parse("18:2D9,12").unwrap()), [9, 12]);
+    }
+
+    #[test]
+    fn order() {
+        let linoleic = parse("18:2Δ12,9").unwrap();
+        assert_eq!(positions(&linoleic), [9, 12]);
+        assert_eq!(linoleic.omega(), Some(6));
+        assert_eq!(linoleic, parse("18:2Δ9,12").unwrap());
+        assert!(parse("18:2Δ9,9").is_err());
+    }
+
+    #[test]
+    fn carbons() {
+        let palmitic = parse("C16:0").unwrap();
+        assert_eq!(palmitic, FattyAcid::new(16, 0));
+        assert_eq!(palmitic.saturation(), Saturation::Saturated);
+        assert_eq!(palmitic.formula(), Some(Formula::new(16, 32, 2)));
+    }
+
+    #[test]
+    fn substituents() {
+        let ricinoleic = parse("18:1Δ9c;12OH").unwrap();
+        assert_eq!(ricinoleic.substituents, [Substituent::Hydroxy(12)]);
+        assert_eq!(ricinoleic.formula(), Some(Formula::new(18, 34, 3)));
+        let vernolic = parse("18:1Δ9c;12Ep").unwrap();
+        assert_eq!(vernolic.substituents, [Substituent::Epoxy(12)]);
+        assert_eq!(vernolic.formula(), Some(Formula::new(18, 32, 3)));
+    }
+
+    #[test]
+    fn errors() {
+        for s in [
+            "2:5", "20:4n-17", "128:0", "1800", "18:", "18:0n-3", "18:2Δ9c", "18:1Δ18", "18:1;12X",
+            "18:1x",
+        ] {
+            assert!(parse(s).is_err(), "{s}");
+        }
+    }
+}
@@ -0,0 +1,283 @@
+pub use self::window::Window;
+use crate::{
+    config::InputUnit, fatty_acid::Library, input::Layout, output::Value, tag::Notation,
+    utils::minimize, Config, Input, Specie, Tag, Tags,
+};
+use anyhow::{ensure, Context, Result};
+use indexmap::IndexMap;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{default::default, ops::Bound};
+
+/// Sum of squares of an exact fit, the remaining starts are skipped
+const EXACT: f64 = 1e-9;
+
+/// Number of the solver starts
+const STARTS: usize = 8;
+
+/// Seed of the random starts, fixed so the results are reproducible
+const SEED: u64 = 0;
+
+/// Positional composition fitted to the measured values
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Fit {
+    /// Fitted sn-1,3, sn-2 and sn-1,2,3 values of the fatty acids, mole
+    /// percent
+    pub input: Input,
+    /// Fitted values by the labels of the measured values
+    pub fitted: IndexMap<String, f64>,
+    /// Measured minus fitted values
+    pub residuals: IndexMap<String, f64>,
+}
+
+impl Fit {
+    /// Sum of the squared residuals
+    pub fn sum_of_squares(&self) -> f64 {
+        self.residuals
+            .values()
+            .map(|residual| residual.powi(2))
+            .sum()
+    }
+}
+
+/// Measured values, a label and a value per line. The labels are the
+/// triacylglycerols in any notation or the groups as in the output (`POP 12.5`,
+/// `ECN 44 20.1`).
+pub fn measured(text: &str) -> Result<IndexMap<String, f64>> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (label, value) = line
+                .rsplit_once(char::is_whitespace)
+                .with_context(|| format!("Parse line ({line}) as label and value"))?;
+            let value = value
+                .parse()
+                .with_context(|| format!("Parse value ({value}) as float"))?;
+            Ok((label.trim().to_owned(), value))
+        })
+        .collect()
+}
+
+/// Fit the sn-1,3 and sn-2 compositions of the fatty acids to the measured
+/// values by constrained least squares: each composition is non-negative and
+/// sums to 100. The forward model is the output of the input by the config,
+/// grouped by the composition, so the measured values can be of the
+/// triacylglycerols or of the groups. The sum of squares is not convex for the
+/// grouped compositions, the best fit of several starts is kept. Fails if the
+/// layout is not of the triacylglycerols or if the output is in weight percent
+/// and any molar mass is unknown.
+pub fn fit(
+    specie: &Specie,
+    fatty_acids: &[String],
+    measured: &IndexMap<String, f64>,
+    layout: Layout,
+    library: &Library,
+    config: &Config,
+) -> Result<Fit> {
+    // The sn-1,2,3 column is the mean of the three positions
+    ensure!(
+        layout == Layout::Triacylglycerol,
+        "Fit ({specie}): expected triacylglycerol input"
+    );
+    let config = Config {
+        bound: Bound::Unbounded,
+        calculation: None,
+        input_unit: InputUnit::MolPercent,
+        pattern: default(),
+        propagation: None,
+        query: None,
+        ..config.clone()
+    };
+    let count = fatty_acids.len();
+    let input = |parameters: &[f64]| {
+        let values = fatty_acids
+            .iter()
+            .enumerate()
+            .map(|(index, fatty_acid)| {
+                let sn13 = parameters[index];
+                let sn2 = parameters[count + index];
+                (
+                    fatty_acid.clone(),
+                    vec![sn13, sn2, (2.0 * sn13 + sn2) / 3.0],
+                )
+            })
+            .collect();
+        let species = [(specie.clone(), values)].into_iter().collect();
+        Input::new(species, default(), default(), layout, library.clone())
+    };
+    let tags = measured
+        .keys()
+        .map(|label| Tag::parse(label, layout, fatty_acids, library).ok())
+        .collect::<Vec<_>>();
+    let fitted = |parameters: &[f64]| -> IndexMap<String, f64> {
+        // The masses are checked before the descent
+        let output = input(parameters).output(&config).unwrap_or_default();
+        let output = output.configure(&config);
+        let values = output.get(specie).cloned().unwrap_or_default();
+        measured
+            .keys()
+            .zip(&tags)
+            .map(|(label, tag)| {
+                let value = labelled(label, tag.as_ref(), &values, &config.notation, library)
+                    .unwrap_or_default();
+                (label.clone(), value)
+            })
+            .collect()
+    };
+    let sum_of_squares = |parameters: &[f64]| -> f64 {
+        measured
+            .values()
+            .zip(fitted(parameters).values())
+            .map(|(measured, fitted)| (measured - fitted).powi(2))
+            .sum()
+    };
+    let descend = |start| {
+        minimize(
+            sum_of_squares,
+            |parameters: &mut [f64]| {
+                let (sn13, sn2) = parameters.split_at_mut(count);
+                simplex(sn13, 100.0);
+                simplex(sn2, 100.0);
+            },
+            start,
+        )
+    };
+    // The uniform composition and random compositions as the starts
+    let uniform = vec![100.0 / count.max(1) as f64; 2 * count];
+    input(&uniform).output(&config)?;
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let (mut parameters, mut value) = descend(uniform);
+    for _ in 1..STARTS {
+        if value < EXACT {
+            break;
+        }
+        let mut start = (0..2 * count)
+            .map(|_| -f64::ln(1.0 - rng.gen::<f64>()))
+            .collect::<Vec<_>>();
+        let (sn13, sn2) = start.split_at_mut(count);
+        for values in [sn13, sn2] {
+            let sum = values.iter().sum::<f64>();
+            values.iter_mut().for_each(|value| *value *= 100.0 / sum);
+        }
+        let (candidate, candidate_value) = descend(start);
+        if candidate_value < value {
+            parameters = candidate;
+            value = candidate_value;
+        }
+    }
+    let fitted = fitted(&parameters);
+    let residuals = measured
+        .iter()
+        .zip(fitted.values())
+        .map(|((label, measured), fitted)| (label.clone(), measured - fitted))
+        .collect();
+    Ok(Fit {
+        input: input(&parameters),
+        fitted,
+        residuals,
+    })
+}
+
+/// Value of the label: the group as in the output (`ECN 44`, `S2U`) or the
+/// group of the parsed tag in any notation (`POP`, `TG 16:0/18:1/16:0`)
+fn labelled(
+    label: &str,
+    tag: Option<&Tag>,
+    values: &IndexMap<Tags, Value>,
+    notation: &Notation,
+    library: &Library,
+) -> Option<f64> {
+    values
+        .iter()
+        .find(|(tags, _)| notation.tags(tags, library, false) == label)
+        .or_else(|| {
+            let tag = tag?;
+            values.iter().find(|(tags, _)| tags.contains(tag))
+        })
+        .map(|(_, value)| value.mean)
+}
+
+/// Euclidean projection onto the simplex: non-negative values with the sum
+fn simplex(values: &mut [f64], sum: f64) {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+    let mut cumulative = 0.0;
+    let mut theta = 0.0;
+    for (index, value) in sorted.iter().enumerate() {
+        cumulative += value;
+        let candidate = (cumulative - sum) / (index + 1) as f64;
+        if *value > candidate {
+            theta = candidate;
+        }
+    }
+    for value in values {
+        *value = (*value - theta).max(0.0);
+    }
+}
+
+mod window;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specie() -> Specie {
+        Specie::from(vec!["X".to_owned()])
+    }
+
+    fn fatty_acids() -> Vec<String> {
+        vec!["P".to_owned(), "O".to_owned()]
+    }
+
+    #[test]
+    fn exact() {
+        let config = Config::default();
+        let library = Library::default();
+        let values = [("P", [60.0, 20.0]), ("O", [40.0, 80.0])]
+            .into_iter()
+            .map(|(fatty_acid, [sn13, sn2])| {
+                (
+                    fatty_acid.to_owned(),
+                    vec![sn13, sn2, (2.0 * sn13 + sn2) / 3.0],
+                )
+            })
+            .collect();
+        let species = [(specie(), values)].into_iter().collect();
+        let input = Input::new(species, default(), default(), default(), library.clone());
+        let output = input.output(&config).unwrap().configure(&config);
+        let measured = output[&specie()]
+            .iter()
+            .map(|(tags, value)| (config.notation.tags(tags, &library, false), value.mean))
+            .collect::<IndexMap<_, _>>();
+        let fit = fit(
+            &specie(),
+            &fatty_acids(),
+            &measured,
+            Layout::Triacylglycerol,
+            &library,
+            &config,
+        )
+        .unwrap();
+        assert!(fit.sum_of_squares() < 1e-6, "{}", fit.sum_of_squares());
+        let fitted = &fit.input[&specie()];
+        assert!((fitted["P"][0] - 60.0).abs() < 1e-2);
+        assert!((fitted["P"][1] - 20.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn layout() {
+        let measured = measured("PO 50\nOO 50").unwrap();
+        let library = Library::default();
+        let config = Config::default();
+        let fit = fit(
+            &specie(),
+            &fatty_acids(),
+            &measured,
+            Layout::Diacylglycerol,
+            &library,
+            &config,
+        );
+        assert!(fit.is_err());
+    }
+}
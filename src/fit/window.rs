@@ -0,0 +1,150 @@
+use super::{fit, measured, Fit};
+use crate::{input::Layout, utils::Task, Config, Input, Specie};
+use anyhow::Result;
+use egui::{
+    Button, Color32, ComboBox, Context, Grid, RichText, ScrollArea, TextEdit, Window as EguiWindow,
+};
+use serde::{Deserialize, Serialize};
+
+/// Fit window: the positional composition fitted to the measured values
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Window {
+    pub open: bool,
+    /// Specie of the fitted fatty acids
+    specie: Option<Specie>,
+    text: String,
+    fit: Option<Fit>,
+    error: Option<String>,
+    /// Running fit
+    #[serde(skip)]
+    task: Option<Task<Result<Fit>>>,
+}
+
+impl Window {
+    pub fn window(&mut self, ctx: &Context, input: &mut Input, config: &Config) {
+        let mut open = self.open;
+        EguiWindow::new("📐 Fit")
+            .open(&mut open)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                if input.layout() != Layout::Triacylglycerol {
+                    ui.colored_label(Color32::YELLOW, "⚠ Triacylglycerol input only");
+                    return;
+                }
+                ComboBox::from_label("Fatty acids")
+                    .selected_text(
+                        self.specie
+                            .as_ref()
+                            .map(ToString::to_string)
+                            .unwrap_or_default(),
+                    )
+                    .show_ui(ui, |ui| {
+                        for specie in input.species() {
+                            let text = specie.to_string();
+                            ui.selectable_value(&mut self.specie, Some(specie), text);
+                        }
+                    })
+                    .response
+                    .on_hover_text("Fatty acids of the specie");
+                ui.label("Measured:").on_hover_text(
+                    "A label and a value per line, the labels as in the output (POP 12.5)",
+                );
+                ui.add(TextEdit::multiline(&mut self.text).code_editor());
+                if let Some(specie) = &self.specie {
+                    let running = self.task.is_some();
+                    if ui.add_enabled(!running, Button::new("Fit")).clicked() {
+                        self.fit = None;
+                        self.error = None;
+                        match measured(&self.text) {
+                            Ok(measured) => {
+                                let fatty_acids = input
+                                    .get(specie)
+                                    .map(|fatty_acids| {
+                                        fatty_acids.keys().cloned().collect::<Vec<_>>()
+                                    })
+                                    .unwrap_or_default();
+                                let fitted = Specie::from(vec![format!("{specie} (fitted)")]);
+                                let layout = input.layout();
+                                let library = input.library().clone();
+                                let config = config.clone();
+                                self.task = Some(Task::spawn(move || {
+                                    fit(&fitted, &fatty_acids, &measured, layout, &library, &config)
+                                }));
+                            }
+                            Err(error) => self.error = Some(format!("{error:#}")),
+                        }
+                    }
+                }
+                if let Some(task) = &self.task {
+                    match task.poll() {
+                        Some(result) => {
+                            match result.and_then(|fit| fit) {
+                                Ok(fit) => self.fit = Some(fit),
+                                Err(error) => self.error = Some(format!("{error:#}")),
+                            }
+                            self.task = None;
+                        }
+                        None => {
+                            ui.spinner();
+                            ctx.request_repaint();
+                        }
+                    }
+                }
+                if let Some(error) = &self.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                let fit = match &self.fit {
+                    Some(fit) => fit,
+                    None => return,
+                };
+                ui.separator();
+                ScrollArea::vertical().show(ui, |ui| {
+                    for (specie, fatty_acids) in &fit.input {
+                        ui.heading(specie.to_string());
+                        Grid::new("fitted").striped(true).show(ui, |ui| {
+                            ui.label("");
+                            ui.label("sn 1, 3");
+                            ui.label("sn 2");
+                            ui.end_row();
+                            for (fatty_acid, values) in fatty_acids {
+                                ui.label(fatty_acid);
+                                ui.label(format!("{:.2}%", values[0]));
+                                ui.label(format!("{:.2}%", values[1]));
+                                ui.end_row();
+                            }
+                        });
+                    }
+                    ui.separator();
+                    Grid::new("residuals").striped(true).show(ui, |ui| {
+                        ui.label("");
+                        ui.label("Measured");
+                        ui.label("Fitted");
+                        ui.label("Residual");
+                        ui.end_row();
+                        for ((label, residual), fitted) in
+                            fit.residuals.iter().zip(fit.fitted.values())
+                        {
+                            ui.label(label);
+                            ui.label(format!("{:.4}%", fitted + residual));
+                            ui.label(format!("{fitted:.4}%"));
+                            ui.label(format!("{residual:.4}"));
+                            ui.end_row();
+                        }
+                        ui.label(RichText::new("∑ residual²").heading());
+                        ui.label(format!("{:.6}", fit.sum_of_squares()));
+                        ui.end_row();
+                    });
+                });
+                if ui
+                    .button("+ specie")
+                    .on_hover_text("Add the fitted specie to the input")
+                    .clicked()
+                {
+                    for (specie, fatty_acids) in fit.input.clone() {
+                        input.insert(specie, fatty_acids);
+                    }
+                }
+            });
+        self.open = open;
+    }
+}
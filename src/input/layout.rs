@@ -0,0 +1,95 @@
+use anyhow::{bail, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Positional layout of the input values and of the acylglycerols
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum Layout {
+    /// Triacylglycerols: the sn-1,3, sn-2 and sn-1,2,3 values, optionally
+    /// followed by the stereospecific sn-1 and sn-3 values
+    #[default]
+    Triacylglycerol,
+    /// Diacylglycerols: the sn-1, sn-2 and sn-1,2 values
+    Diacylglycerol,
+    /// Phospholipids of an unknown head group, the layout of the
+    /// diacylglycerols
+    Phospholipid,
+    /// 2-Monoacylglycerols of the lipolysis, not an input layout: the sn-2
+    /// value
+    Monoacylglycerol,
+}
+
+impl Layout {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Triacylglycerol => "Triacylglycerols",
+            Self::Diacylglycerol => "Diacylglycerols",
+            Self::Phospholipid => "Phospholipids",
+            Self::Monoacylglycerol => "Monoacylglycerols",
+        }
+    }
+
+    /// LIPID MAPS abbreviation of the class, `PL` for the phospholipids of
+    /// an unknown head group
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Triacylglycerol => "TG",
+            Self::Diacylglycerol => "DG",
+            Self::Phospholipid => "PL",
+            Self::Monoacylglycerol => "MG",
+        }
+    }
+
+    /// Number of the acyl positions
+    pub fn positions(&self) -> usize {
+        match self {
+            Self::Triacylglycerol => 3,
+            Self::Diacylglycerol | Self::Phospholipid => 2,
+            Self::Monoacylglycerol => 1,
+        }
+    }
+
+    /// Keys of the values in the table layout, in the order of the array
+    /// layout
+    pub fn keys(&self) -> &'static [&'static str] {
+        match self {
+            Self::Triacylglycerol => &["sn13", "sn2", "sn123", "sn1", "sn3"],
+            Self::Diacylglycerol | Self::Phospholipid => &["sn1", "sn2", "sn12"],
+            Self::Monoacylglycerol => &["sn2"],
+        }
+    }
+
+    /// Headers of the value columns
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            Self::Triacylglycerol => &["sn 1, 3", "sn 2", "sn 1, 2, 3", "sn 1", "sn 3"],
+            Self::Diacylglycerol | Self::Phospholipid => &["sn 1", "sn 2", "sn 1, 2"],
+            Self::Monoacylglycerol => &["sn 2"],
+        }
+    }
+
+    /// Compositions of the positions from the composition of the outer
+    /// positions (sn-1,3 or sn-1) and of sn-2
+    pub fn positional(&self, outer: f64, sn2: f64) -> Vec<f64> {
+        match self {
+            Self::Triacylglycerol => vec![outer, sn2, outer],
+            Self::Diacylglycerol | Self::Phospholipid => vec![outer, sn2],
+            Self::Monoacylglycerol => vec![sn2],
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match &*s.to_lowercase() {
+            "tag" | "triacylglycerol" => Ok(Self::Triacylglycerol),
+            "dag" | "diacylglycerol" => Ok(Self::Diacylglycerol),
+            "pl" | "phospholipid" => Ok(Self::Phospholipid),
+            _ => bail!("Parse layout ({s})"),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
+    config::{Calculation, InputUnit},
+    input::{calculated, convert, effective, is_stereospecific},
     specie::Specie,
-    utils::{CollapsingStateExt, IndexMapExt, UiExt},
+    utils::{CollapsingStateExt, UiExt},
     Input,
 };
 use egui::{
@@ -17,8 +19,6 @@ use std::{
 };
 use tracing::error;
 
-const SN: [&str; 3] = ["sn 1, 3", "sn 2", "sn 1, 2, 3"];
-
 // fn temp(fatty_acids: &IndexMap<String, Vec<f64>>, fatty_acid: &String) {
 //     let value = fatty_acids.shift_remove(fatty_acid)?;
 //     let key = self.texts.remove(fatty_acid);
@@ -29,6 +29,10 @@ const SN: [&str; 3] = ["sn 1, 3", "sn 2", "sn 1, 2, 3"];
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct List {
     pub input: Input,
+    pub calculation: Option<Calculation>,
+    pub input_unit: InputUnit,
+    /// Show the values converted to mole percent
+    pub converted: bool,
     pub edit: bool,
     pub open: Option<bool>,
 
@@ -50,7 +54,7 @@ impl List {
                 for specie in &self.input.species() {
                     let remove = self.collapsing(ui, specie, open);
                     if remove {
-                        self.input.remove(specie);
+                        self.input.remove_specie(specie);
                     }
                 }
                 if self.edit && ui.button("+").on_hover_text("+ specie").clicked() {
@@ -66,6 +70,16 @@ impl List {
             .show_header(ui, |ui| {
                 ui.toggle_value(selected, RichText::from(specie.to_string()).heading())
                     .on_hover_text(specie.taxonomy("."));
+                let mut stereospecific = is_stereospecific(&self.input[specie]);
+                if self.edit
+                    && self.input.layout().keys().len() > 3
+                    && ui
+                        .toggle_value(&mut stereospecific, "sn 1 ≠ sn 3")
+                        .on_hover_text("Stereospecific")
+                        .changed()
+                {
+                    self.input.set_stereospecific(specie, stereospecific);
+                }
                 self.edit && ui.button("-").on_hover_text("- specie").clicked()
             })
             .body(|ui| {
@@ -76,17 +90,52 @@ impl List {
     }
 
     fn table(&mut self, ui: &mut Ui, specie: &Specie) {
-        let &mut Self { size, .. } = self;
+        let &mut Self {
+            calculation,
+            input_unit,
+            size,
+            ..
+        } = self;
+        let layout = self.input.layout();
+        let columns = layout.columns();
+        let count = if is_stereospecific(&self.input[specie]) {
+            columns.len()
+        } else {
+            3
+        };
+        let converted = if self.converted {
+            convert(&self.input[specie], input_unit, self.input.library())
+        } else {
+            None
+        };
+        let unit = if converted.is_some() {
+            InputUnit::MolPercent.name()
+        } else {
+            input_unit.name()
+        };
         TableBuilder::new(ui)
             .striped(true)
             .cell_layout(Layout::centered_and_justified(Direction::LeftToRight))
-            .columns(Column::auto().resizable(true), 4)
+            .columns(Column::auto().resizable(true), 1 + count)
             .column(Column::exact(size))
             .header(size, |mut row| {
-                row.col(|_ui| {});
-                for index in 0..3 {
+                row.col(|ui| {
+                    if self.converted && converted.is_none() {
+                        ui.label("⚠")
+                            .on_hover_text("Unknown molar mass, the values are not converted");
+                    }
+                });
+                for (index, column) in columns.iter().take(count).enumerate() {
                     row.col(|ui| {
-                        ui.label(SN[index]);
+                        if calculation.map(|calculation| calculation.index()) == Some(index) {
+                            ui.label(RichText::new(*column).italics())
+                                .on_hover_text(format!(
+                                    "{}, {unit}",
+                                    Calculation::name(&calculation)
+                                ));
+                        } else {
+                            ui.label(*column).on_hover_text(format!("Measured, {unit}"));
+                        }
                     });
                 }
                 row.col(|_ui| {});
@@ -105,37 +154,91 @@ impl List {
                                     .or_insert(fatty_acid.clone());
                                 lost_focus = ui.text_edit_singleline(text).lost_focus();
                             } else {
-                                ui.label(fatty_acid.to_string());
+                                ui.label(fatty_acid.to_string())
+                                    .on_hover_text(self.input.library().description(fatty_acid));
                             }
                         });
-                        let values = &mut self.input[specie][fatty_acid];
-                        // sn123 = (sn1 + sn2 + sn3) / 3.0 = (2.0 * sn13 + sn2) / 3.0
-                        let sn123 = (2.0 * values[0] + values[1]) / 3.0;
-                        // sn13 = (3.0 * sn123 - sn2) / 2.0
-                        let sn13 = (3.0 * values[2] - values[1]) / 2.0;
-                        // sn2 = 3.0 * sn123 - 2.0 * sn13
-                        let sn2 = 3.0 * values[2] - 2.0 * values[0];
-                        let calculated = [sn13, sn2, sn123];
-                        for (index, value) in values.iter_mut().enumerate() {
-                            row.col(|ui| {
-                                if self.edit {
-                                    ui.drag_percent(value)
-                                } else {
-                                    ui.label(format!("{value:05.2}%"))
-                                }
-                                .on_hover_text(format!("{:.2}%", calculated[index]));
-                            });
+                        if let Some(converted) = &converted {
+                            let values = &converted[fatty_acid];
+                            let calculated = calculated(values, layout);
+                            for (index, value) in values.iter().take(count).enumerate() {
+                                row.col(|ui| {
+                                    let text = if calculation.map(|calculation| calculation.index())
+                                        == Some(index)
+                                    {
+                                        RichText::new(format!("{:05.2}%", calculated[index]))
+                                            .italics()
+                                    } else {
+                                        RichText::new(format!("{value:05.2}%"))
+                                    };
+                                    let measured = self.input[specie][fatty_acid][index];
+                                    ui.label(text).on_hover_text(format!(
+                                        "{}: {measured:.2}%",
+                                        input_unit.name()
+                                    ));
+                                });
+                            }
+                        } else {
+                            let deviations = self
+                                .input
+                                .deviations(specie, fatty_acid)
+                                .cloned()
+                                .unwrap_or_default();
+                            let replicates = self
+                                .input
+                                .replicates(specie, fatty_acid)
+                                .into_iter()
+                                .cloned()
+                                .collect::<Vec<_>>();
+                            let values = &mut self.input[specie][fatty_acid];
+                            let calculated = calculated(values, layout);
+                            for (index, value) in values.iter_mut().take(count).enumerate() {
+                                row.col(|ui| {
+                                    if calculation.map(|calculation| calculation.index())
+                                        == Some(index)
+                                    {
+                                        ui.label(
+                                            RichText::new(format!("{:05.2}%", calculated[index]))
+                                                .italics(),
+                                        )
+                                        .on_hover_text(format!("Measured: {value:.2}%"));
+                                    } else {
+                                        let response = if self.edit && replicates.is_empty() {
+                                            ui.drag_percent(value)
+                                        } else if let Some(deviation) = deviations.get(index) {
+                                            ui.label(format!("{value:05.2} ± {deviation:.2}%"))
+                                        } else {
+                                            ui.label(format!("{value:05.2}%"))
+                                        };
+                                        let response = if replicates.is_empty() {
+                                            response
+                                        } else {
+                                            let replicates = replicates
+                                                .iter()
+                                                .filter_map(|replicate| replicate.get(index))
+                                                .map(|replicate| format!("{replicate:.2}%"))
+                                                .join(", ");
+                                            response.on_hover_text(format!(
+                                                "Mean of the replicates: {replicates}"
+                                            ))
+                                        };
+                                        if let Some(calculated) = calculated.get(index) {
+                                            response.on_hover_text(format!(
+                                                "Calculated: {calculated:.2}%"
+                                            ));
+                                        }
+                                    }
+                                });
+                            }
                         }
                         row.col(|ui| {
                             if self.edit && ui.button("-").on_hover_text("- fatty acid").clicked() {
-                                self.input[specie].remove(fatty_acid);
+                                self.input.remove_fatty_acid(specie, fatty_acid);
                             }
                         });
                         if lost_focus && &self.texts[specie][fatty_acid] != fatty_acid {
                             let text = self.texts[specie].remove(fatty_acid).unwrap();
-                            if !self.input[specie].contains_key(&text) {
-                                self.input[specie].replace(fatty_acid, text);
-                            }
+                            self.input.rename_fatty_acid(specie, fatty_acid, text);
                         }
                     });
                 }
@@ -144,7 +247,11 @@ impl List {
                     body.row(size, |mut row| {
                         row.col(|ui| {
                             if ui.button("+").on_hover_text("+ fatty acid").clicked() {
-                                self.input[specie].insert(String::new(), vec![0.0, 0.0, 0.0]);
+                                self.input.insert_fatty_acid(
+                                    specie,
+                                    String::new(),
+                                    vec![0.0; count],
+                                );
                             }
                         });
                     });
@@ -154,10 +261,13 @@ impl List {
                     row.col(|ui| {
                         ui.heading("∑");
                     });
-                    let fatty_acids = &self.input[specie];
-                    for index in 0..3 {
+                    let fatty_acids = converted.as_ref().unwrap_or(&self.input[specie]);
+                    for index in 0..count {
                         row.col(|ui| {
-                            let sum = fatty_acids.values().map(|value| value[index]).sum::<f64>();
+                            let sum = fatty_acids
+                                .values()
+                                .map(|values| effective(values, calculation, layout)[index])
+                                .sum::<f64>();
                             let count = fatty_acids.len();
                             ui.label(format!("{sum:.1}%"))
                                 .on_hover_text(format!("{count}"));
@@ -1,51 +1,244 @@
-use crate::{Output, Specie, Tag};
+use crate::{
+    config::{Calculation, InputUnit, Model, OutputUnit, Propagation},
+    fatty_acid::Library,
+    output::Value,
+    utils::IndexMapExt,
+    Config, Output, Specie, Tag,
+};
+use anyhow::{Context, Result};
 use indexmap::{
     map::{IntoIter, Iter, IterMut},
     IndexMap,
 };
 use itertools::Itertools;
+pub use layout::Layout;
 pub use list::List;
 use serde::{Deserialize, Serialize};
 use std::{
+    default::default,
     iter::once,
     ops::{Deref, DerefMut},
 };
 pub use text::Text;
 
 /// Input
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-pub struct Input(IndexMap<Specie, IndexMap<String, Vec<f64>>>);
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Input {
+    species: IndexMap<Specie, IndexMap<String, Vec<f64>>>,
+    /// Standard deviations of the values
+    deviations: IndexMap<Specie, IndexMap<String, Vec<f64>>>,
+    /// Replicate values, the values are the means of the replicates
+    replicates: IndexMap<Specie, Vec<IndexMap<String, Vec<f64>>>>,
+    layout: Layout,
+    library: Library,
+}
 
 impl Input {
-    pub fn new(input: IndexMap<Specie, IndexMap<String, Vec<f64>>>) -> Self {
-        Self(input)
+    pub fn new(
+        species: IndexMap<Specie, IndexMap<String, Vec<f64>>>,
+        deviations: IndexMap<Specie, IndexMap<String, Vec<f64>>>,
+        replicates: IndexMap<Specie, Vec<IndexMap<String, Vec<f64>>>>,
+        layout: Layout,
+        library: Library,
+    ) -> Self {
+        Self {
+            species,
+            deviations,
+            replicates,
+            layout,
+            library,
+        }
     }
 
-    pub fn output(&self) -> Output {
-        Output::new(
-            self.0
-                .iter()
-                .map(|(key, value)| {
-                    let key = key.clone();
-                    let value = (0..3)
-                        .map(|_| value.keys())
-                        .multi_cartesian_product()
-                        .map(|key| {
-                            let tag = Tag::new([key[0].clone(), key[1].clone(), key[2].clone()]);
-                            let value =
-                                value[&tag[0]][0] * value[&tag[1]][1] * value[&tag[2]][0] * 0.0001;
-                            let tags = once(tag).collect();
-                            (tags, value)
-                        })
-                        .collect();
-                    (key, value)
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    /// Standard deviations of the fatty acid values
+    pub fn deviations(&self, specie: &Specie, fatty_acid: &str) -> Option<&Vec<f64>> {
+        self.deviations.get(specie)?.get(fatty_acid)
+    }
+
+    /// Replicate values of the fatty acid
+    pub fn replicates(&self, specie: &Specie, fatty_acid: &str) -> Vec<&Vec<f64>> {
+        self.replicates
+            .get(specie)
+            .into_iter()
+            .flatten()
+            .filter_map(|replicate| replicate.get(fatty_acid))
+            .collect()
+    }
+
+    pub fn remove_specie(&mut self, specie: &Specie) {
+        self.species.remove(specie);
+        self.deviations.remove(specie);
+        self.replicates.remove(specie);
+    }
+
+    pub fn insert_fatty_acid(&mut self, specie: &Specie, fatty_acid: String, values: Vec<f64>) {
+        for replicate in self.replicates.get_mut(specie).into_iter().flatten() {
+            replicate.insert(fatty_acid.clone(), values.clone());
+        }
+        self.species[specie].insert(fatty_acid, values);
+    }
+
+    pub fn remove_fatty_acid(&mut self, specie: &Specie, fatty_acid: &str) {
+        self.species[specie].remove(fatty_acid);
+        if let Some(deviations) = self.deviations.get_mut(specie) {
+            deviations.remove(fatty_acid);
+        }
+        for replicate in self.replicates.get_mut(specie).into_iter().flatten() {
+            replicate.remove(fatty_acid);
+        }
+    }
+
+    /// Rename the fatty acid, unless the name is taken
+    pub fn rename_fatty_acid(&mut self, specie: &Specie, from: &str, to: String) {
+        if self.species[specie].contains_key(&to) {
+            return;
+        }
+        if let Some(deviations) = self.deviations.get_mut(specie) {
+            deviations.replace(from, to.clone());
+        }
+        for replicate in self.replicates.get_mut(specie).into_iter().flatten() {
+            replicate.replace(from, to.clone());
+        }
+        self.species[specie].replace(from, to);
+    }
+
+    /// Resize the values of the specie to the stereospecific (with sn-1 and
+    /// sn-3) or the non-stereospecific length
+    pub fn set_stereospecific(&mut self, specie: &Specie, stereospecific: bool) {
+        let count = self.layout.keys().len();
+        let deviations = self
+            .deviations
+            .get_mut(specie)
+            .into_iter()
+            .flat_map(IndexMap::values_mut);
+        let replicates = self
+            .replicates
+            .get_mut(specie)
+            .into_iter()
+            .flatten()
+            .flat_map(IndexMap::values_mut);
+        for values in self.species[specie]
+            .values_mut()
+            .chain(deviations)
+            .chain(replicates)
+        {
+            if stereospecific {
+                values.resize(count, values[0]);
+            } else {
+                values.truncate(3);
+            }
+        }
+    }
+
+    /// Acylglycerol composition of the positions of the layout. The species
+    /// with replicates are calculated replicate by replicate, the replicate
+    /// standard deviations take place of the propagated ones. Fails if the
+    /// input or the output is in weight percent and any molar mass is unknown.
+    pub fn output(&self, config: &Config) -> Result<Output> {
+        let acylglycerols = |fatty_acids: &IndexMap<String, Vec<f64>>| {
+            acylglycerols(fatty_acids, self.layout, &self.library, config)
+        };
+        let species = self
+            .species
+            .iter()
+            .map(|(specie, fatty_acids)| {
+                let context =
+                    || format!("Convert the input ({specie}) to mole percent: unknown molar mass");
+                let values = acylglycerols(fatty_acids).with_context(context)?;
+                let replicates = self
+                    .replicates
+                    .get(specie)
+                    .filter(|replicates| replicates.len() > 1)
+                    .map(|replicates| {
+                        replicates
+                            .iter()
+                            .map(acylglycerols)
+                            .collect::<Option<Vec<_>>>()
+                            .with_context(context)
+                    })
+                    .transpose()?;
+                let deviations = self.deviations.get(specie);
+                let deviations = deviations.filter(|_| replicates.is_none());
+                let terms = deviations.and_then(|deviations| match config.propagation? {
+                    Propagation::Analytical => propagation::analytical(
+                        fatty_acids,
+                        deviations,
+                        self.layout,
+                        &self.library,
+                        config,
+                    ),
+                    Propagation::MonteCarlo => propagation::monte_carlo(
+                        fatty_acids,
+                        deviations,
+                        self.layout,
+                        &self.library,
+                        config,
+                    ),
+                });
+                let values = values
+                    .into_iter()
+                    .map(|(tag, value)| {
+                        let value = match &replicates {
+                            Some(replicates) => Value::from_replicates(
+                                replicates
+                                    .iter()
+                                    .map(|replicate| {
+                                        replicate.get(&tag).copied().unwrap_or_default()
+                                    })
+                                    .collect(),
+                            ),
+                            None => match &terms {
+                                Some(terms) => Value::propagated(
+                                    value,
+                                    terms.get(&tag).cloned().unwrap_or_default(),
+                                ),
+                                None => Value::new(value, None),
+                            },
+                        };
+                        (once(tag).collect(), value)
+                    })
+                    .collect();
+                Ok((specie.clone(), values))
+            })
+            .collect::<Result<_>>()?;
+        let output = Output::new(species, self.library.clone()).ordered(&self.fatty_acids());
+        match config.output_unit {
+            OutputUnit::MolPercent => Ok(output),
+            OutputUnit::WeightPercent => output
+                .weight()
+                .context("Convert the output to weight percent: unknown molar mass"),
+        }
+    }
+
+    /// Fatty acids of the specie converted to mole percent, the calculated
+    /// column replaced. Returns `None` if any molar mass is unknown.
+    pub fn mole_percent(
+        &self,
+        specie: &Specie,
+        config: &Config,
+    ) -> Option<IndexMap<String, Vec<f64>>> {
+        let fatty_acids = convert(self.species.get(specie)?, config.input_unit, &self.library)?;
+        Some(
+            fatty_acids
+                .into_iter()
+                .map(|(fatty_acid, values)| {
+                    let values = effective(&values, config.calculation, self.layout);
+                    (fatty_acid, values)
                 })
                 .collect(),
         )
     }
 
     pub fn fatty_acids(&self) -> Vec<String> {
-        self.0
+        self.species
             .values()
             .flat_map(IndexMap::keys)
             .unique()
@@ -54,7 +247,7 @@ impl Input {
     }
 
     pub fn species(&self) -> Vec<Specie> {
-        self.0.keys().cloned().collect()
+        self.species.keys().cloned().collect()
     }
 }
 
@@ -64,7 +257,7 @@ impl IntoIterator for Input {
     type IntoIter = IntoIter<Specie, IndexMap<String, Vec<f64>>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.species.into_iter()
     }
 }
 
@@ -74,7 +267,7 @@ impl<'a> IntoIterator for &'a Input {
     type IntoIter = Iter<'a, Specie, IndexMap<String, Vec<f64>>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter()
+        self.species.iter()
     }
 }
 
@@ -84,7 +277,7 @@ impl<'a> IntoIterator for &'a mut Input {
     type IntoIter = IterMut<'a, Specie, IndexMap<String, Vec<f64>>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter_mut()
+        self.species.iter_mut()
     }
 }
 
@@ -92,15 +285,531 @@ impl Deref for Input {
     type Target = IndexMap<Specie, IndexMap<String, Vec<f64>>>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.species
     }
 }
 
 impl DerefMut for Input {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.species
     }
 }
 
+/// Acylglycerol composition of the specie fatty acids: the products of the
+/// positional compositions. Returns `None` if the input is not in mole
+/// percent and any molar mass is unknown.
+fn acylglycerols(
+    fatty_acids: &IndexMap<String, Vec<f64>>,
+    layout: Layout,
+    library: &Library,
+    config: &Config,
+) -> Option<IndexMap<Tag, f64>> {
+    let fatty_acids = convert(fatty_acids, config.input_unit, library)?;
+    let fatty_acids = fatty_acids
+        .iter()
+        .map(|(key, values)| (key.clone(), effective(values, config.calculation, layout)))
+        .collect();
+    let positional = positional(&fatty_acids, config.model, layout);
+    let acylglycerols = (0..layout.positions())
+        .map(|_| positional.keys())
+        .multi_cartesian_product()
+        .map(|key| {
+            let tag = Tag::new(layout, key.into_iter().cloned().collect::<Vec<_>>());
+            let value = tag
+                .iter()
+                .enumerate()
+                .map(|(index, fatty_acid)| positional[fatty_acid][index] / 100.0)
+                .product::<f64>()
+                * 100.0;
+            (tag, value)
+        })
+        .collect();
+    Some(acylglycerols)
+}
+
+/// Triacylglycerol composition of the specie of the mole percent fatty acids,
+/// the calculated column already replaced
+pub fn calculate(
+    specie: Specie,
+    fatty_acids: IndexMap<String, Vec<f64>>,
+    library: &Library,
+    config: &Config,
+) -> Result<Output> {
+    let species = once((specie, fatty_acids)).collect();
+    let input = Input::new(species, default(), default(), default(), library.clone());
+    input.output(&Config {
+        calculation: None,
+        input_unit: InputUnit::MolPercent,
+        propagation: None,
+        ..config.clone()
+    })
+}
+
+/// Values converted to mole percent of fatty acids, each column is normalized
+/// separately. None if a molar mass of the fatty acids is unknown.
+pub fn convert(
+    fatty_acids: &IndexMap<String, Vec<f64>>,
+    unit: InputUnit,
+    library: &Library,
+) -> Option<IndexMap<String, Vec<f64>>> {
+    if let InputUnit::MolPercent = unit {
+        return Some(fatty_acids.clone());
+    }
+    let masses = fatty_acids
+        .keys()
+        .map(|key| {
+            let mut formula = library.fatty_acid(key)?.formula()?;
+            // Methyl ester: + CH2
+            if let InputUnit::FameWeightPercent = unit {
+                formula.carbons = formula.carbons.checked_add(1)?;
+                formula.hydrogens = formula.hydrogens.checked_add(2)?;
+            }
+            Some(formula.average_mass())
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let count = fatty_acids.values().map(Vec::len).max().unwrap_or_default();
+    let sums = (0..count)
+        .map(|index| {
+            fatty_acids
+                .values()
+                .zip(&masses)
+                .map(|(values, mass)| values.get(index).unwrap_or(&0.0) / mass)
+                .sum::<f64>()
+        })
+        .collect::<Vec<_>>();
+    Some(
+        fatty_acids
+            .iter()
+            .zip(&masses)
+            .map(|((key, values), mass)| {
+                let values = values
+                    .iter()
+                    .zip(&sums)
+                    .map(|(value, &sum)| normalize(value / mass, sum))
+                    .collect();
+                (key.clone(), values)
+            })
+            .collect(),
+    )
+}
+
+/// Calculated values of each column (sn-1,3, sn-2, sn-1,2,3) from the other two
+/// columns, the outer column of the diacylglycerols is sn-1
+pub fn calculated(values: &[f64], layout: Layout) -> [f64; 3] {
+    let positions = layout.positions() as f64;
+    // sn13 = (3.0 * sn123 - sn2) / 2.0
+    let outer = (positions * values[2] - values[1]) / (positions - 1.0);
+    // sn2 = 3.0 * sn123 - 2.0 * sn13
+    let sn2 = positions * values[2] - (positions - 1.0) * values[0];
+    // sn123 = (sn1 + sn2 + sn3) / 3.0 = (2.0 * sn13 + sn2) / 3.0
+    let total = ((positions - 1.0) * values[0] + values[1]) / positions;
+    [outer, sn2, total]
+}
+
+/// Stereospecific input has separate sn-1 and sn-3 values after the sn-1,3,
+/// sn-2 and sn-1,2,3 values
+pub fn is_stereospecific(fatty_acids: &IndexMap<String, Vec<f64>>) -> bool {
+    !fatty_acids.is_empty() && fatty_acids.values().all(|values| values.len() >= 5)
+}
+
+/// Values with the calculated column replaced
+pub fn effective(values: &[f64], calculation: Option<Calculation>, layout: Layout) -> Vec<f64> {
+    let mut effective = values.to_vec();
+    if let Some(calculation) = calculation {
+        let index = calculation.index();
+        effective[index] = calculated(values, layout)[index];
+    }
+    effective
+}
+
+/// Positional (sn-1, sn-2, sn-3) composition of the specie fatty acids by the
+/// model. The stereospecific sn-1 and sn-3 values split the sn-1,3 composition
+/// of the models, except of 1,2,3-random: the positions are randomized.
+fn positional(
+    fatty_acids: &IndexMap<String, Vec<f64>>,
+    model: Model,
+    layout: Layout,
+) -> IndexMap<String, Vec<f64>> {
+    // sn-1 and sn-3 differ from sn-1,3 by half of the stereospecific
+    // difference, so every position still sums to 100
+    let stereospecific = is_stereospecific(fatty_acids) && layout == Layout::Triacylglycerol;
+    let positional = |values: &[f64], outer: f64, sn2: f64| match values {
+        &[_, _, _, sn1, sn3, ..] if stereospecific => {
+            let difference = (sn1 - sn3) / 2.0;
+            vec![outer + difference, sn2, outer - difference]
+        }
+        _ => layout.positional(outer, sn2),
+    };
+    match model {
+        Model::OneTwoThreeRandom => fatty_acids
+            .iter()
+            .map(|(key, values)| (key.clone(), vec![values[2]; layout.positions()]))
+            .collect(),
+        Model::OneThreeRandomTwoRandom if stereospecific => fatty_acids
+            .iter()
+            .map(|(key, values)| (key.clone(), vec![values[3], values[1], values[4]]))
+            .collect(),
+        Model::OneThreeRandomTwoRandom => fatty_acids
+            .iter()
+            .map(|(key, values)| (key.clone(), layout.positional(values[0], values[1])))
+            .collect(),
+        Model::VanderWal => fatty_acids
+            .iter()
+            .map(|(key, values)| {
+                let outer = calculated(values, layout)[0];
+                (key.clone(), positional(values, outer, values[1]))
+            })
+            .collect(),
+        Model::CorrectedOneThreeRandomTwoRandom => {
+            let corrected = fatty_acids
+                .iter()
+                .map(|(key, values)| {
+                    let outer = calculated(values, layout)[0];
+                    let positional = positional(values, outer, values[1]);
+                    let positional = positional.into_iter().map(|value| value.max(0.0));
+                    (key.clone(), positional.collect::<Vec<_>>())
+                })
+                .collect::<IndexMap<_, _>>();
+            let sums = (0..layout.positions())
+                .map(|index| corrected.values().map(|values| values[index]).sum::<f64>())
+                .collect::<Vec<_>>();
+            corrected
+                .into_iter()
+                .map(|(key, values)| {
+                    let values = values
+                        .into_iter()
+                        .zip(&sums)
+                        .map(|(value, &sum)| normalize(value, sum))
+                        .collect();
+                    (key, values)
+                })
+                .collect()
+        }
+    }
+}
+
+fn normalize(value: f64, sum: f64) -> f64 {
+    if sum == 0.0 {
+        return 0.0;
+    }
+    100.0 * value / sum
+}
+
+mod layout;
 pub mod list;
+mod propagation;
 pub mod text;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fatty_acid::Formula;
+
+    fn specie() -> Specie {
+        Specie::from(vec!["X".to_owned()])
+    }
+
+    fn fatty_acids(values: &[(&str, &[f64])]) -> IndexMap<String, Vec<f64>> {
+        values
+            .iter()
+            .map(|&(fatty_acid, values)| (fatty_acid.to_owned(), values.to_vec()))
+            .collect()
+    }
+
+    fn input(values: &[(&str, &[f64])]) -> Input {
+        let species = once((specie(), fatty_acids(values))).collect();
+        Input::new(species, default(), default(), default(), default())
+    }
+
+    /// Values of the unconfigured output by the tags
+    fn values(input: &Input, config: &Config) -> IndexMap<String, Value> {
+        input.output(config).unwrap()[&specie()]
+            .iter()
+            .map(|(tags, value)| (tags.iter().join(", "), value.clone()))
+            .collect()
+    }
+
+    fn sum(values: &IndexMap<String, Value>) -> f64 {
+        values.values().map(|value| value.mean).sum()
+    }
+
+    fn assert_approx(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    /// sn-1,3 60 and 40, sn-2 20 and 80, sn-1,2,3 consistent
+    fn palmitic_oleic() -> Input {
+        input(&[
+            ("P", &[60.0, 20.0, 140.0 / 3.0]),
+            ("O", &[40.0, 80.0, 160.0 / 3.0]),
+        ])
+    }
+
+    fn model(model: Model) -> Config {
+        Config { model, ..default() }
+    }
+
+    #[test]
+    fn models() {
+        for model in [
+            Model::OneTwoThreeRandom,
+            Model::OneThreeRandomTwoRandom,
+            Model::VanderWal,
+            Model::CorrectedOneThreeRandomTwoRandom,
+        ] {
+            let values = values(&palmitic_oleic(), &self::model(model));
+            assert_eq!(values.len(), 8);
+            assert_approx(sum(&values), 100.0);
+        }
+    }
+
+    #[test]
+    fn one_three_random_two_random() {
+        let values = values(&palmitic_oleic(), &model(Model::OneThreeRandomTwoRandom));
+        assert_approx(values["PPP"].mean, 7.2);
+        assert_approx(values["POP"].mean, 28.8);
+        assert_approx(values["POO"].mean, 19.2);
+        assert_approx(values["OOP"].mean, 19.2);
+        assert_approx(values["OPO"].mean, 3.2);
+    }
+
+    #[test]
+    fn one_two_three_random() {
+        let values = values(&palmitic_oleic(), &model(Model::OneTwoThreeRandom));
+        assert_approx(values["PPP"].mean, 100.0 * (140.0f64 / 300.0).powi(3));
+        assert_approx(values["POP"].mean, values["PPO"].mean);
+    }
+
+    #[test]
+    fn vander_wal() {
+        // The measured sn-1,3 values are replaced by the calculated ones
+        let input = input(&[
+            ("P", &[50.0, 20.0, 140.0 / 3.0]),
+            ("O", &[50.0, 80.0, 160.0 / 3.0]),
+        ]);
+        let values = values(&input, &model(Model::VanderWal));
+        assert_approx(values["PPP"].mean, 7.2);
+        assert_approx(values["POP"].mean, 28.8);
+    }
+
+    #[test]
+    fn corrected() {
+        // The calculated sn-1,3 value of P is negative: -10
+        let input = input(&[("P", &[0.0, 80.0, 20.0]), ("O", &[100.0, 20.0, 80.0])]);
+        let uncorrected = values(&input, &model(Model::VanderWal));
+        assert!(uncorrected["POO"].mean < 0.0);
+        let values = values(&input, &model(Model::CorrectedOneThreeRandomTwoRandom));
+        assert_approx(sum(&values), 100.0);
+        assert_approx(values["OPO"].mean, 80.0);
+        assert_approx(values["OOO"].mean, 20.0);
+        assert_approx(values["POP"].mean, 0.0);
+    }
+
+    /// sn-1 80 and 20, sn-3 40 and 60 of the sn-1,3 of the palmitic-oleic
+    fn stereospecific() -> Input {
+        input(&[
+            ("P", &[60.0, 20.0, 140.0 / 3.0, 80.0, 40.0]),
+            ("O", &[40.0, 80.0, 160.0 / 3.0, 20.0, 60.0]),
+        ])
+    }
+
+    #[test]
+    fn stereospecific_models() {
+        for model in [
+            Model::OneThreeRandomTwoRandom,
+            Model::VanderWal,
+            Model::CorrectedOneThreeRandomTwoRandom,
+        ] {
+            let values = values(&stereospecific(), &self::model(model));
+            assert_approx(sum(&values), 100.0);
+            assert_approx(values["PPP"].mean, 6.4);
+            // The enantiomers differ
+            assert_approx(values["POO"].mean, 38.4);
+            assert_approx(values["OOP"].mean, 6.4);
+        }
+    }
+
+    #[test]
+    fn stereospecific_random() {
+        // The positions are randomized, the stereospecific values are not used
+        let config = model(Model::OneTwoThreeRandom);
+        let stereospecific = values(&stereospecific(), &config);
+        let values = values(&palmitic_oleic(), &config);
+        for (tag, value) in values {
+            assert_approx(stereospecific[&tag].mean, value.mean);
+        }
+    }
+
+    #[test]
+    fn conversion() {
+        let library = Library::default();
+        let fatty_acids = fatty_acids(&[("P", &[50.0, 20.0]), ("O", &[50.0, 80.0])]);
+        let converted = convert(&fatty_acids, InputUnit::MolPercent, &library).unwrap();
+        assert_eq!(converted, fatty_acids);
+        for (unit, [palmitic, oleic]) in [
+            (
+                InputUnit::FaWeightPercent,
+                [Formula::new(16, 32, 2), Formula::new(18, 34, 2)],
+            ),
+            (
+                InputUnit::FameWeightPercent,
+                [Formula::new(17, 34, 2), Formula::new(19, 36, 2)],
+            ),
+        ] {
+            let converted = convert(&fatty_acids, unit, &library).unwrap();
+            let (palmitic, oleic) = (palmitic.average_mass(), oleic.average_mass());
+            let expected = 100.0 * (50.0 / palmitic) / (50.0 / palmitic + 50.0 / oleic);
+            assert_approx(converted["P"][0], expected);
+            for index in 0..2 {
+                assert_approx(converted["P"][index] + converted["O"][index], 100.0);
+            }
+            // The lighter fatty acid has more moles
+            assert!(converted["P"][0] > 50.0);
+        }
+    }
+
+    #[test]
+    fn conversion_unknown() {
+        let fatty_acids = fatty_acids(&[("P", &[50.0]), ("X", &[50.0])]);
+        let library = Library::default();
+        assert!(convert(&fatty_acids, InputUnit::FaWeightPercent, &library).is_none());
+        let input = input(&[("P", &[50.0, 50.0, 50.0]), ("X", &[50.0, 50.0, 50.0])]);
+        let config = Config {
+            input_unit: InputUnit::FaWeightPercent,
+            ..default()
+        };
+        assert!(input.output(&config).is_err());
+    }
+
+    #[test]
+    fn weight() {
+        let library = Library::default();
+        let input = palmitic_oleic();
+        let moles = values(&input, &default());
+        let weights = values(
+            &input,
+            &Config {
+                output_unit: OutputUnit::WeightPercent,
+                ..default()
+            },
+        );
+        assert_approx(sum(&weights), 100.0);
+        let mass = |tag: &str| {
+            let fatty_acids = tag.chars().map(String::from).collect::<Vec<_>>();
+            let tag = Tag::new(Layout::Triacylglycerol, fatty_acids);
+            tag.formula(&library).unwrap().average_mass()
+        };
+        assert_approx(
+            weights["PPP"].mean / weights["OOO"].mean,
+            moles["PPP"].mean * mass("PPP") / (moles["OOO"].mean * mass("OOO")),
+        );
+        // The lighter triacylglycerols have less weight than moles
+        assert!(weights["PPP"].mean < moles["PPP"].mean);
+        assert!(weights["OOO"].mean > moles["OOO"].mean);
+    }
+
+    #[test]
+    fn weight_unknown() {
+        let input = input(&[("P", &[50.0, 50.0, 50.0]), ("X", &[50.0, 50.0, 50.0])]);
+        let config = Config {
+            output_unit: OutputUnit::WeightPercent,
+            ..default()
+        };
+        assert!(input.output(&default()).is_ok());
+        assert!(input.output(&config).is_err());
+    }
+
+    fn deviated(deviation: f64) -> Input {
+        let deviations = fatty_acids(&[("P", &[deviation; 3]), ("O", &[deviation; 3])]);
+        let palmitic_oleic = palmitic_oleic();
+        Input::new(
+            palmitic_oleic.species,
+            once((specie(), deviations)).collect(),
+            default(),
+            default(),
+            default(),
+        )
+    }
+
+    fn propagation(propagation: Propagation) -> Config {
+        Config {
+            propagation: Some(propagation),
+            draws: 10_000,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn propagation_zero() {
+        for propagation in [Propagation::Analytical, Propagation::MonteCarlo] {
+            let values = values(&deviated(0.0), &self::propagation(propagation));
+            for value in values.values() {
+                assert_approx(value.deviation.unwrap_or_default(), 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn propagation_agreement() {
+        let input = deviated(1.0);
+        let analytical = values(&input, &propagation(Propagation::Analytical));
+        let monte_carlo = values(&input, &propagation(Propagation::MonteCarlo));
+        for (tag, analytical) in analytical {
+            let monte_carlo = &monte_carlo[&tag];
+            let (expected, actual) = (
+                analytical.deviation.unwrap(),
+                monte_carlo.deviation.unwrap(),
+            );
+            assert!(expected > 0.0, "{tag}");
+            assert!(
+                (actual - expected).abs() < 0.1 * expected,
+                "{tag}: {actual} != {expected}"
+            );
+            assert_approx(monte_carlo.mean, analytical.mean);
+        }
+    }
+
+    fn replicated(replicates: &[&[(&str, &[f64])]]) -> Input {
+        let replicates = replicates
+            .iter()
+            .map(|replicate| fatty_acids(replicate))
+            .collect::<Vec<_>>();
+        let palmitic_oleic = palmitic_oleic();
+        Input::new(
+            palmitic_oleic.species,
+            default(),
+            once((specie(), replicates)).collect(),
+            default(),
+            default(),
+        )
+    }
+
+    #[test]
+    fn replicates() {
+        let input = replicated(&[
+            &[
+                ("P", &[70.0, 20.0, 160.0 / 3.0]),
+                ("O", &[30.0, 80.0, 140.0 / 3.0]),
+            ],
+            &[("P", &[50.0, 20.0, 40.0]), ("O", &[50.0, 80.0, 60.0])],
+        ]);
+        let values = values(&input, &model(Model::OneThreeRandomTwoRandom));
+        // PPP of the replicates: 9.8 and 5.0
+        assert_approx(values["PPP"].mean, 7.4);
+        assert_approx(values["PPP"].deviation.unwrap(), 4.8 / 2f64.sqrt());
+        assert_eq!(values["PPP"].replicates.len(), 2);
+        assert_approx(sum(&values), 100.0);
+    }
+
+    #[test]
+    fn replicates_same() {
+        let replicate: &[(&str, &[f64])] = &[
+            ("P", &[60.0, 20.0, 140.0 / 3.0]),
+            ("O", &[40.0, 80.0, 160.0 / 3.0]),
+        ];
+        let values = values(&replicated(&[replicate, replicate]), &default());
+        for value in values.values() {
+            assert_approx(value.deviation.unwrap(), 0.0);
+        }
+    }
+}
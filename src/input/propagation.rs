@@ -0,0 +1,94 @@
+use super::{acylglycerols, Layout};
+use crate::{fatty_acid::Library, utils::FloatExt, Config, Tag};
+use indexmap::IndexMap;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::f64::consts::TAU;
+
+/// Relative step of the central differences
+const STEP: f64 = 1e-6;
+
+/// Seed of the Monte Carlo draws, fixed so the results are reproducible
+const SEED: u64 = 0;
+
+/// First-order propagation: the terms of the deviations of the acylglycerols,
+/// the partial derivatives (central differences) by every input value times
+/// its deviation
+pub fn analytical(
+    fatty_acids: &IndexMap<String, Vec<f64>>,
+    deviations: &IndexMap<String, Vec<f64>>,
+    layout: Layout,
+    library: &Library,
+    config: &Config,
+) -> Option<IndexMap<Tag, Vec<f64>>> {
+    let mut terms = IndexMap::<Tag, Vec<f64>>::new();
+    for (fatty_acid, deviations) in deviations {
+        let values = match fatty_acids.get(fatty_acid) {
+            Some(values) => values,
+            None => continue,
+        };
+        for (index, (&value, &deviation)) in values.iter().zip(deviations).enumerate() {
+            if deviation.is_approx_zero() {
+                continue;
+            }
+            let step = STEP * value.abs().max(1.0);
+            let mut perturbed = fatty_acids.clone();
+            perturbed[fatty_acid][index] = value + step;
+            let plus = acylglycerols(&perturbed, layout, library, config)?;
+            perturbed[fatty_acid][index] = value - step;
+            let minus = acylglycerols(&perturbed, layout, library, config)?;
+            for ((tag, plus), minus) in plus.into_iter().zip(minus.into_values()) {
+                let derivative = (plus - minus) / (2.0 * step);
+                terms.entry(tag).or_default().push(derivative * deviation);
+            }
+        }
+    }
+    Some(terms)
+}
+
+/// Monte Carlo propagation: the terms of the deviations of the acylglycerols
+/// over the draws of normally distributed input values, the centered draws
+/// over the root of the count less one
+pub fn monte_carlo(
+    fatty_acids: &IndexMap<String, Vec<f64>>,
+    deviations: &IndexMap<String, Vec<f64>>,
+    layout: Layout,
+    library: &Library,
+    config: &Config,
+) -> Option<IndexMap<Tag, Vec<f64>>> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut draws = IndexMap::<Tag, Vec<f64>>::new();
+    for _ in 0..config.draws {
+        let mut sampled = fatty_acids.clone();
+        for (fatty_acid, values) in &mut sampled {
+            if let Some(deviations) = deviations.get(fatty_acid) {
+                for (value, &deviation) in values.iter_mut().zip(deviations) {
+                    *value += deviation * normal(&mut rng);
+                }
+            }
+        }
+        for (tag, value) in acylglycerols(&sampled, layout, library, config)? {
+            draws.entry(tag).or_default().push(value);
+        }
+    }
+    let count = config.draws.saturating_sub(1).max(1) as f64;
+    Some(
+        draws
+            .into_iter()
+            .map(|(tag, draws)| {
+                let mean = draws.iter().sum::<f64>() / draws.len().max(1) as f64;
+                let terms = draws
+                    .into_iter()
+                    .map(|draw| (draw - mean) / count.sqrt())
+                    .collect();
+                (tag, terms)
+            })
+            .collect(),
+    )
+}
+
+/// Standard normal sample (Box-Muller transform)
+fn normal(rng: &mut impl Rng) -> f64 {
+    let u = 1.0 - rng.gen::<f64>();
+    let v = rng.gen::<f64>();
+    (-2.0 * u.ln()).sqrt() * (TAU * v).cos()
+}
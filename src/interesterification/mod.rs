@@ -0,0 +1,192 @@
+pub use self::window::Window;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Interesterification
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Interesterification {
+    #[default]
+    Chemical,
+    Enzymatic,
+}
+
+impl Interesterification {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Chemical => "Chemical",
+            Self::Enzymatic => "sn-1,3 lipase",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Chemical => "Chemical interesterification: 1,2,3-random distribution",
+            Self::Enzymatic => {
+                "sn-1,3 specific enzymatic interesterification or acidolysis with the donor"
+            }
+        }
+    }
+}
+
+/// Chemical interesterification: the fatty acids randomized over the
+/// positions, every position gets the sn-1,2,3 value (1,2,3-random)
+pub fn chemical(fatty_acids: &IndexMap<String, Vec<f64>>) -> IndexMap<String, Vec<f64>> {
+    fatty_acids
+        .iter()
+        .map(|(fatty_acid, values)| (fatty_acid.clone(), vec![values[2]; values.len()]))
+        .collect()
+}
+
+/// sn-1,3 specific enzymatic interesterification, acidolysis with the donor
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Enzymatic {
+    /// Fatty acid donor (free fatty acids or esters), normalized on calculation
+    pub donor: IndexMap<String, f64>,
+    /// Moles of the donor fatty acids per mole of the triacylglycerols
+    pub ratio: f64,
+    /// Degree of the incorporation, percent of the equilibrium
+    pub incorporation: f64,
+    /// Acyl migration rate, percent of the sn-2 acyls exchanged with the
+    /// sn-1,3 acyls, `None` without the migration
+    pub migration: Option<f64>,
+}
+
+impl Enzymatic {
+    /// The sn-1,3 acyls are exchanged with the donor: at the equilibrium the
+    /// sn-1 and sn-3 positions get the composition of the pool of the two sn-1,3
+    /// acyls and the donor, the incorporation moves sn-1,3 toward it. The sn-2
+    /// acyls are unchanged unless they migrate. The stereospecific sn-1 and
+    /// sn-3 values are exchanged separately.
+    pub fn interesterify(
+        &self,
+        fatty_acids: &IndexMap<String, Vec<f64>>,
+    ) -> IndexMap<String, Vec<f64>> {
+        let sum = self.donor.values().sum::<f64>();
+        let ratio = if sum > 0.0 { self.ratio.max(0.0) } else { 0.0 };
+        let incorporation = self.incorporation.clamp(0.0, 100.0) / 100.0;
+        let migration = self.migration.unwrap_or_default().clamp(0.0, 100.0) / 100.0;
+        let count = fatty_acids.values().map(Vec::len).max().unwrap_or(3);
+        let mut fatty_acids = fatty_acids.clone();
+        for fatty_acid in self.donor.keys() {
+            if !fatty_acids.contains_key(fatty_acid) {
+                fatty_acids.insert(fatty_acid.clone(), vec![0.0; count]);
+            }
+        }
+        fatty_acids
+            .into_iter()
+            .map(|(fatty_acid, values)| {
+                let donor = match self.donor.get(&fatty_acid) {
+                    Some(donor) if sum > 0.0 => 100.0 * donor / sum,
+                    _ => 0.0,
+                };
+                // Stereospecific: sn-1 and sn-3 are exchanged with the same pool
+                let (sn1, sn3) = match values[..] {
+                    [_, _, _, sn1, sn3, ..] => (sn1, sn3),
+                    _ => (values[0], values[0]),
+                };
+                let equilibrium = (sn1 + sn3 + ratio * donor) / (2.0 + ratio);
+                let sn1 = sn1 + incorporation * (equilibrium - sn1);
+                let sn3 = sn3 + incorporation * (equilibrium - sn3);
+                // A sn-2 acyl is exchanged with one of the two sn-1,3 acyls
+                let (sn1, sn2, sn3) = (
+                    sn1 + migration / 2.0 * (values[1] - sn1),
+                    values[1] + migration * ((sn1 + sn3) / 2.0 - values[1]),
+                    sn3 + migration / 2.0 * (values[1] - sn3),
+                );
+                let sn13 = (sn1 + sn3) / 2.0;
+                let sn123 = (2.0 * sn13 + sn2) / 3.0;
+                let mut values = vec![sn13, sn2, sn123, sn1, sn3];
+                values.truncate(count);
+                (fatty_acid, values)
+            })
+            .collect()
+    }
+}
+
+impl Default for Enzymatic {
+    fn default() -> Self {
+        Self {
+            donor: IndexMap::new(),
+            ratio: 0.0,
+            incorporation: 100.0,
+            migration: None,
+        }
+    }
+}
+
+mod window;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::default::default;
+
+    fn fatty_acids() -> IndexMap<String, Vec<f64>> {
+        [
+            ("P", [60.0, 20.0, 140.0 / 3.0]),
+            ("O", [40.0, 80.0, 160.0 / 3.0]),
+        ]
+        .into_iter()
+        .map(|(fatty_acid, values)| (fatty_acid.to_owned(), values.to_vec()))
+        .collect()
+    }
+
+    #[test]
+    fn randomized() {
+        let interesterified = chemical(&fatty_acids());
+        assert_eq!(interesterified["P"], [140.0 / 3.0; 3]);
+        assert_eq!(interesterified["O"], [160.0 / 3.0; 3]);
+    }
+
+    fn assert_approx(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    fn enzymatic(donor: &[(&str, f64)], ratio: f64, migration: Option<f64>) -> Enzymatic {
+        Enzymatic {
+            donor: donor
+                .iter()
+                .map(|&(fatty_acid, value)| (fatty_acid.to_owned(), value))
+                .collect(),
+            ratio,
+            migration,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn acidolysis() {
+        let interesterified = enzymatic(&[("O", 100.0)], 2.0, None).interesterify(&fatty_acids());
+        // The pool of the two sn-1,3 acyls and two donor acyls
+        assert_approx(interesterified["P"][0], 30.0);
+        assert_approx(interesterified["O"][0], 70.0);
+        // sn-2 is unchanged without the migration
+        assert_approx(interesterified["P"][1], 20.0);
+        for index in 0..3 {
+            let sum = interesterified
+                .values()
+                .map(|values| values[index])
+                .sum::<f64>();
+            assert_approx(sum, 100.0);
+        }
+    }
+
+    #[test]
+    fn donor() {
+        // The donor fatty acid not in the oil is added
+        let interesterified = enzymatic(&[("L", 100.0)], 1.0, None).interesterify(&fatty_acids());
+        assert_approx(interesterified["L"][0], 100.0 / 3.0);
+        assert_approx(interesterified["L"][1], 0.0);
+        // Without the donor the sn-1,3 acyls are only exchanged between sn-1 and sn-3
+        let interesterified = enzymatic(&[], 1.0, None).interesterify(&fatty_acids());
+        assert_eq!(interesterified, fatty_acids());
+    }
+
+    #[test]
+    fn migration() {
+        let interesterified = enzymatic(&[], 0.0, Some(100.0)).interesterify(&fatty_acids());
+        assert_approx(interesterified["P"][1], 60.0);
+        assert_approx(interesterified["P"][0], 40.0);
+        assert_approx(interesterified["P"][2], 140.0 / 3.0);
+    }
+}
@@ -0,0 +1,189 @@
+use super::{chemical, Enzymatic, Interesterification};
+use crate::{
+    blend::Blend,
+    fit::measured,
+    input::{calculate, Layout},
+    output::difference,
+    Config, Input, Specie,
+};
+use egui::{Color32, ComboBox, Context, DragValue, Grid, TextEdit, Ui, Window as EguiWindow};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Interesterification window: the triacylglycerols of the specie before and
+/// after the interesterification
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Window {
+    pub open: bool,
+    /// Interesterified specie, an input specie or the blend
+    specie: Option<Specie>,
+    interesterification: Interesterification,
+    enzymatic: Enzymatic,
+    /// Donor composition, a fatty acid and a value per line
+    donor: String,
+}
+
+impl Window {
+    pub fn window(&mut self, ctx: &Context, input: &Input, blend: &Blend, config: &Config) {
+        let mut open = self.open;
+        EguiWindow::new("🔀 Interesterification")
+            .open(&mut open)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                if input.layout() != Layout::Triacylglycerol {
+                    ui.colored_label(Color32::YELLOW, "⚠ Triacylglycerol input only");
+                    return;
+                }
+                ComboBox::from_label("Specie")
+                    .selected_text(
+                        self.specie
+                            .as_ref()
+                            .map(ToString::to_string)
+                            .unwrap_or_default(),
+                    )
+                    .show_ui(ui, |ui| {
+                        let mut species = input.species();
+                        if !blend.components.is_empty() {
+                            species.push(Blend::specie());
+                        }
+                        for specie in species {
+                            let text = specie.to_string();
+                            ui.selectable_value(&mut self.specie, Some(specie), text);
+                        }
+                    })
+                    .response
+                    .on_hover_text("Specie or the blend");
+                ComboBox::from_label("Interesterification")
+                    .selected_text(self.interesterification.name())
+                    .show_ui(ui, |ui| {
+                        for interesterification in [
+                            Interesterification::Chemical,
+                            Interesterification::Enzymatic,
+                        ] {
+                            ui.selectable_value(
+                                &mut self.interesterification,
+                                interesterification,
+                                interesterification.name(),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(self.interesterification.description());
+                if let Interesterification::Enzymatic = self.interesterification {
+                    self.enzymatic(ui);
+                }
+                let specie = match &self.specie {
+                    Some(specie) => specie,
+                    None => return,
+                };
+                let fatty_acids = if input.contains_key(specie) {
+                    input.mole_percent(specie, config)
+                } else if *specie == Blend::specie() {
+                    blend.fatty_acids(input, config)
+                } else {
+                    return;
+                };
+                let fatty_acids = match fatty_acids {
+                    Some(fatty_acids) => fatty_acids,
+                    None => {
+                        ui.colored_label(Color32::YELLOW, "⚠ Unknown molar mass");
+                        return;
+                    }
+                };
+                let interesterified = match self.interesterification {
+                    Interesterification::Chemical => chemical(&fatty_acids),
+                    Interesterification::Enzymatic => self.enzymatic.interesterify(&fatty_acids),
+                };
+                ui.label(self.interesterification.description());
+                ui.separator();
+                positional(ui, &fatty_acids, &interesterified);
+                ui.separator();
+                let library = input.library();
+                let before = Specie::from(vec!["Before".to_owned()]);
+                let after = Specie::from(vec!["After".to_owned()]);
+                match (
+                    calculate(before, fatty_acids, library, config),
+                    calculate(after, interesterified, library, config),
+                ) {
+                    (Ok(before), Ok(after)) => difference(ui, before, after, config),
+                    (Err(error), _) | (_, Err(error)) => {
+                        ui.colored_label(Color32::RED, format!("{error:#}"));
+                    }
+                }
+            });
+        self.open = open;
+    }
+
+    fn enzymatic(&mut self, ui: &mut Ui) {
+        ui.label("Donor:")
+            .on_hover_text("A fatty acid and a value per line (P 100)");
+        ui.add(TextEdit::multiline(&mut self.donor).code_editor());
+        match measured(&self.donor) {
+            Ok(donor) => self.enzymatic.donor = donor,
+            Err(error) => {
+                ui.colored_label(Color32::RED, format!("{error:#}"));
+            }
+        }
+        Grid::new("enzymatic").show(ui, |ui| {
+            ui.label("Molar ratio")
+                .on_hover_text("Moles of the donor fatty acids per mole of the triacylglycerols");
+            ui.add(
+                DragValue::new(&mut self.enzymatic.ratio)
+                    .clamp_range(0.0..=100.0)
+                    .speed(0.1),
+            );
+            ui.end_row();
+            ui.label("Incorporation")
+                .on_hover_text("Degree of the incorporation, percent of the equilibrium");
+            ui.add(
+                DragValue::new(&mut self.enzymatic.incorporation)
+                    .clamp_range(0.0..=100.0)
+                    .speed(0.1)
+                    .suffix("%"),
+            );
+            ui.end_row();
+            let mut migration = self.enzymatic.migration.is_some();
+            ui.checkbox(&mut migration, "Acyl migration")
+                .on_hover_text("Percent of the sn-2 acyls exchanged with the sn-1,3 acyls");
+            match (migration, &mut self.enzymatic.migration) {
+                (true, Some(rate)) => {
+                    ui.add(
+                        DragValue::new(rate)
+                            .clamp_range(0.0..=100.0)
+                            .speed(0.1)
+                            .suffix("%"),
+                    );
+                }
+                (true, rate) => *rate = Some(0.0),
+                (false, rate) => *rate = None,
+            }
+            ui.end_row();
+        });
+    }
+}
+
+/// Positional composition of the fatty acids before and after
+fn positional(
+    ui: &mut Ui,
+    before: &IndexMap<String, Vec<f64>>,
+    after: &IndexMap<String, Vec<f64>>,
+) {
+    Grid::new("positional").striped(true).show(ui, |ui| {
+        ui.label("");
+        ui.label("sn 1, 3");
+        ui.label("");
+        ui.label("sn 2");
+        ui.label("");
+        ui.end_row();
+        for (fatty_acid, after) in after {
+            let before = before.get(fatty_acid);
+            ui.label(fatty_acid);
+            for index in 0..2 {
+                let before = before.map_or(0.0, |values| values[index]);
+                ui.label(format!("{before:.2}% → {:.2}%", after[index]));
+                ui.label(format!("{:+.2}", after[index] - before));
+            }
+            ui.end_row();
+        }
+    });
+}
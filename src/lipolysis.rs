@@ -0,0 +1,148 @@
+use crate::{input::Layout, output::Value, Tag, Tags};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+/// Products of the partial hydrolysis of the triacylglycerols by a sn-1,3
+/// specific lipase. The sn-1 and sn-3 esters are hydrolyzed independently by
+/// the degree of the hydrolysis, without the acyl migration. The
+/// 2,3-diacylglycerols are merged with the 1,2-diacylglycerols as their
+/// enantiomers (`PO` and `SO` of `POS`), the monoacylglycerols are of sn-2.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Products {
+    /// Moles per 100 moles of the initial triacylglycerols
+    pub triacylglycerols: IndexMap<Tag, f64>,
+    pub diacylglycerols: IndexMap<Tag, f64>,
+    pub monoacylglycerols: IndexMap<Tag, f64>,
+    pub fatty_acids: IndexMap<String, f64>,
+}
+
+impl Products {
+    /// Products of the triacylglycerols (mole percent, before configure), the
+    /// degree is the percent of the hydrolyzed sn-1 and sn-3 esters, the tags
+    /// of the other acylglycerols are skipped
+    pub fn new(values: &IndexMap<Tags, Value>, degree: f64) -> Self {
+        let hydrolyzed = degree.clamp(0.0, 100.0) / 100.0;
+        let remained = 1.0 - hydrolyzed;
+        let mut products = Self::default();
+        for (tags, value) in values {
+            for tag in tags {
+                let (sn1, sn2, sn3) = match (tag.layout(), &tag[..]) {
+                    (Layout::Triacylglycerol, [sn1, sn2, sn3]) => (sn1, sn2, sn3),
+                    _ => continue,
+                };
+                let value = value.mean;
+                *products.triacylglycerols.entry(tag.clone()).or_default() +=
+                    value * remained * remained;
+                let diacylglycerols = [
+                    Tag::new(Layout::Diacylglycerol, [sn1.clone(), sn2.clone()]),
+                    Tag::new(Layout::Diacylglycerol, [sn3.clone(), sn2.clone()]),
+                ];
+                for diacylglycerol in diacylglycerols {
+                    *products.diacylglycerols.entry(diacylglycerol).or_default() +=
+                        value * hydrolyzed * remained;
+                }
+                let monoacylglycerol = Tag::new(Layout::Monoacylglycerol, [sn2.clone()]);
+                *products
+                    .monoacylglycerols
+                    .entry(monoacylglycerol)
+                    .or_default() += value * hydrolyzed * hydrolyzed;
+                for fatty_acid in [sn1, sn3] {
+                    *products.fatty_acids.entry(fatty_acid.clone()).or_default() +=
+                        value * hydrolyzed;
+                }
+            }
+        }
+        products
+    }
+}
+
+/// Mole percent of the values
+pub fn normalize<K: Clone + Eq + Hash>(values: &IndexMap<K, f64>) -> IndexMap<K, f64> {
+    let sum = values.values().sum::<f64>();
+    values
+        .iter()
+        .map(|(key, &value)| {
+            let value = if sum == 0.0 { 0.0 } else { 100.0 * value / sum };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+/// Fatty acid composition of the acylglycerols, mole percent
+pub fn composition(acylglycerols: &IndexMap<Tag, f64>) -> IndexMap<String, f64> {
+    let mut fatty_acids = IndexMap::<_, f64>::new();
+    for (tag, value) in acylglycerols {
+        for fatty_acid in tag.iter() {
+            *fatty_acids.entry(fatty_acid.clone()).or_default() += value;
+        }
+    }
+    normalize(&fatty_acids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::once;
+
+    fn tag(layout: Layout, fatty_acids: &[&str]) -> Tag {
+        Tag::new(
+            layout,
+            fatty_acids
+                .iter()
+                .map(|&fatty_acid| fatty_acid.to_owned())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn products(degree: f64) -> Products {
+        let values = [(["P", "O", "S"], 60.0), (["O", "O", "O"], 40.0)]
+            .into_iter()
+            .map(|(fatty_acids, value)| {
+                let tag = tag(Layout::Triacylglycerol, &fatty_acids);
+                (once(tag).collect::<Tags>(), Value::from(value))
+            })
+            .collect();
+        Products::new(&values, degree)
+    }
+
+    #[test]
+    fn classes() {
+        let products = products(30.0);
+        let sum = |values: &IndexMap<Tag, f64>| values.values().sum::<f64>();
+        assert!((sum(&products.triacylglycerols) - 49.0).abs() < 1e-9);
+        assert!((sum(&products.diacylglycerols) - 42.0).abs() < 1e-9);
+        assert!((sum(&products.monoacylglycerols) - 9.0).abs() < 1e-9);
+        assert!((products.fatty_acids.values().sum::<f64>() - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn layouts() {
+        let products = products(50.0);
+        assert!(products
+            .diacylglycerols
+            .keys()
+            .all(|tag| tag.layout() == Layout::Diacylglycerol && tag.len() == 2));
+        assert!(products
+            .monoacylglycerols
+            .keys()
+            .all(|tag| tag.layout() == Layout::Monoacylglycerol && tag.len() == 1));
+        assert!(products
+            .diacylglycerols
+            .contains_key(&tag(Layout::Diacylglycerol, &["S", "O"])));
+        let composition = composition(&products.monoacylglycerols);
+        assert_eq!(composition.keys().collect::<Vec<_>>(), ["O"]);
+        assert!((composition["O"] - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn complete() {
+        let products = products(100.0);
+        assert!(products
+            .triacylglycerols
+            .values()
+            .all(|&value| value == 0.0));
+        assert!(products.diacylglycerols.values().all(|&value| value == 0.0));
+        assert!((products.fatty_acids.values().sum::<f64>() - 200.0).abs() < 1e-9);
+    }
+}
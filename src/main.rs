@@ -48,10 +48,17 @@ fn main() {
 }
 
 mod app;
+mod blend;
 mod config;
 mod dataset;
+mod experimental;
+mod fatty_acid;
+mod fit;
 mod input;
+mod interesterification;
+mod lipolysis;
 mod output;
+mod query;
 mod specie;
 mod tag;
 mod utils;
@@ -0,0 +1,128 @@
+use crate::{
+    input::Layout,
+    lipolysis::{composition, normalize, Products},
+    Output, Tag,
+};
+use egui::{CollapsingHeader, Color32, Grid, RichText, ScrollArea, Slider, Ui};
+use indexmap::IndexMap;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// Lipolysis UI: the products of the sn-1,3 specific lipase
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Lipolysis {
+    /// Output in mole percent
+    pub output: Output,
+    /// Percent of the hydrolyzed sn-1 and sn-3 esters
+    degree: f64,
+}
+
+impl Lipolysis {
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let triacylglycerols = self
+            .output
+            .values()
+            .flat_map(IndexMap::keys)
+            .flatten()
+            .all(|tag| tag.layout() == Layout::Triacylglycerol);
+        if !triacylglycerols {
+            ui.colored_label(Color32::YELLOW, "⚠ Triacylglycerol input only");
+            return;
+        }
+        ui.add(
+            Slider::new(&mut self.degree, 0.0..=100.0)
+                .suffix("%")
+                .text("Hydrolysis"),
+        )
+        .on_hover_text("Percent of the hydrolyzed sn-1 and sn-3 esters");
+        ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                for (specie, values) in &self.output {
+                    let products = Products::new(values, self.degree);
+                    CollapsingHeader::new(RichText::from(format!("{specie}, mol %")).heading())
+                        .id_source(specie)
+                        .show(ui, |ui| {
+                            classes(ui, &products);
+                            ui.separator();
+                            fatty_acids(ui, &products);
+                            CollapsingHeader::new("1,2(2,3)-DAG")
+                                .id_source((specie, "diacylglycerols"))
+                                .show(ui, |ui| {
+                                    acylglycerols(ui, &products.diacylglycerols);
+                                });
+                            CollapsingHeader::new("2-MAG")
+                                .id_source((specie, "monoacylglycerols"))
+                                .show(ui, |ui| {
+                                    acylglycerols(ui, &products.monoacylglycerols);
+                                });
+                        });
+                }
+            });
+    }
+}
+
+/// Moles of the lipid classes per 100 moles of the initial triacylglycerols
+/// and mole percent of the lipid classes
+fn classes(ui: &mut Ui, products: &Products) {
+    let classes = [
+        ("TAG", products.triacylglycerols.values().sum::<f64>()),
+        ("1,2(2,3)-DAG", products.diacylglycerols.values().sum()),
+        ("2-MAG", products.monoacylglycerols.values().sum()),
+        ("FFA", products.fatty_acids.values().sum()),
+    ]
+    .into_iter()
+    .collect::<IndexMap<_, _>>();
+    Grid::new("classes").striped(true).show(ui, |ui| {
+        ui.label("");
+        ui.label("mol / 100 mol TAG")
+            .on_hover_text("Moles per 100 moles of the initial triacylglycerols");
+        ui.label("mol %");
+        ui.end_row();
+        for ((class, moles), percent) in classes.iter().zip(normalize(&classes).values()) {
+            ui.label(*class);
+            ui.label(format!("{moles:.2}"));
+            ui.label(format!("{percent:.2}%"));
+            ui.end_row();
+        }
+    });
+}
+
+/// Fatty acid composition of the lipid classes, mole percent
+fn fatty_acids(ui: &mut Ui, products: &Products) {
+    let classes = [
+        composition(&products.triacylglycerols),
+        composition(&products.diacylglycerols),
+        composition(&products.monoacylglycerols),
+        normalize(&products.fatty_acids),
+    ];
+    Grid::new("fatty_acids").striped(true).show(ui, |ui| {
+        ui.label("");
+        ui.label("TAG");
+        ui.label("1,2(2,3)-DAG");
+        ui.label("2-MAG");
+        ui.label("FFA");
+        ui.end_row();
+        for fatty_acid in classes.iter().flat_map(IndexMap::keys).unique() {
+            ui.label(fatty_acid);
+            for class in &classes {
+                let value = class.get(fatty_acid).copied().unwrap_or_default();
+                ui.label(format!("{value:.2}%"));
+            }
+            ui.end_row();
+        }
+    });
+}
+
+/// Acylglycerols of the class, mole percent
+fn acylglycerols(ui: &mut Ui, acylglycerols: &IndexMap<Tag, f64>) {
+    Grid::new("acylglycerols").striped(true).show(ui, |ui| {
+        let mut acylglycerols = normalize(acylglycerols);
+        acylglycerols.sort_keys();
+        for (tag, value) in acylglycerols {
+            ui.label(tag.to_string());
+            ui.label(format!("{value:.4}%"));
+            ui.end_row();
+        }
+    });
+}
@@ -1,3 +1,4 @@
+use super::{breakdown, names, statistics};
 use crate::{Config, Output};
 use egui::{CollapsingHeader, Grid, RichText, ScrollArea, Ui};
 use serde::{Deserialize, Serialize};
@@ -17,18 +18,29 @@ impl List {
             .auto_shrink([false; 2])
             .show(ui, |ui| {
                 for (specie, value) in &configured {
-                    CollapsingHeader::new(RichText::from(specie.to_string()).heading())
+                    let unit = self.config.output_unit.name();
+                    CollapsingHeader::new(RichText::from(format!("{specie}, {unit}")).heading())
+                        .id_source(specie)
                         .open(self.expand)
                         .show(ui, |ui| {
                             Grid::new("").striped(true).show(ui, |ui| {
-                                for (tags, &value) in value {
-                                    if self.config.composition.is_none() {
-                                        ui.label(tags.to_string());
+                                for (tags, value) in value {
+                                    let response = ui.label(self.config.notation.tags(
+                                        tags,
+                                        self.output.library(),
+                                        self.config.composition.is_some(),
+                                    ));
+                                    if tags.len() > 1 {
+                                        response.on_hover_ui(|ui| {
+                                            breakdown(ui, &self.output, specie, tags, &self.config);
+                                        });
                                     } else {
-                                        ui.label(format!("{tags:#}"));
+                                        response.on_hover_ui(|ui| {
+                                            names(ui, self.output.library(), tags);
+                                        });
                                     }
                                     ui.label(format!("{value:.4}%"))
-                                        .on_hover_text(value.to_string());
+                                        .on_hover_ui(|ui| statistics(ui, value));
                                     ui.end_row();
                                 }
                             });
@@ -49,8 +61,9 @@ impl List {
                                 ui.label(format!("{major}"));
                                 ui.label(format!("{sum}"));
                                 ui.end_row();
-                                let sum = triglycerides.values().sum::<f64>();
-                                let major = filtered.values().sum::<f64>();
+                                let sum =
+                                    triglycerides.values().map(|value| value.mean).sum::<f64>();
+                                let major = filtered.values().map(|value| value.mean).sum::<f64>();
                                 let minor = sum - major;
                                 ui.label(format!("{minor:.1}%"));
                                 ui.label(format!("{major:.1}%"));
@@ -1,21 +1,93 @@
 use crate::{
     config::{Composition, Sort},
-    Config, Specie, Tags,
+    fatty_acid::Library,
+    tag::{Group, Notation},
+    Config, Specie, Tag, Tags,
 };
+use egui::{Grid, RichText, ScrollArea, Ui};
 use indexmap::{map::Iter, IndexMap};
 use itertools::Itertools;
+pub use lipolysis::Lipolysis;
 pub use list::List;
 pub use plot::Plot;
 use serde::{Deserialize, Serialize};
-use std::ops::{Bound, Deref};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    iter::once,
+    ops::{Bound, Deref},
+};
 pub use table::Table;
+pub use value::Value;
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
-pub struct Output(IndexMap<Specie, IndexMap<Tags, f64>>);
+pub struct Output {
+    species: IndexMap<Specie, IndexMap<Tags, Value>>,
+    library: Library,
+    /// Fatty acids in the input order, the order of the fatty acids of the
+    /// specie groups
+    fatty_acids: Vec<String>,
+}
 
 impl Output {
-    pub fn new(output: IndexMap<Specie, IndexMap<Tags, f64>>) -> Self {
-        Self(output)
+    /// The fatty acids are in the order of the first tags they are in
+    pub fn new(species: IndexMap<Specie, IndexMap<Tags, Value>>, library: Library) -> Self {
+        let fatty_acids = species
+            .values()
+            .flat_map(IndexMap::keys)
+            .flatten()
+            .flat_map(|tag| tag.iter())
+            .unique()
+            .cloned()
+            .collect();
+        Self {
+            species,
+            library,
+            fatty_acids,
+        }
+    }
+
+    /// Output with the order of the fatty acids of the other output, so the
+    /// specie groups of both are equal
+    pub fn ordered(mut self, fatty_acids: &[String]) -> Self {
+        let rest = self
+            .fatty_acids
+            .into_iter()
+            .filter(|fatty_acid| !fatty_acids.contains(fatty_acid));
+        self.fatty_acids = fatty_acids.iter().cloned().chain(rest).collect();
+        self
+    }
+
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    pub fn fatty_acids(&self) -> &[String] {
+        &self.fatty_acids
+    }
+
+    /// Add the species of the other output, the library is kept
+    pub fn extend(&mut self, output: Output) {
+        self.species.extend(output.species);
+        for fatty_acid in output.fatty_acids {
+            if !self.fatty_acids.contains(&fatty_acid) {
+                self.fatty_acids.push(fatty_acid);
+            }
+        }
+    }
+
+    /// Average molar mass of the triacylglycerols of the specie, the values
+    /// must be in mole percent. Returns `None` if any mass is unknown.
+    pub fn average_mass(&self, specie: &Specie) -> Option<f64> {
+        let values = self.species.get(specie)?;
+        let sum = values.values().map(|value| value.mean).sum::<f64>();
+        let mass = values
+            .iter()
+            .map(|(tags, value)| {
+                let tag = tags.first()?;
+                Some(tag.formula(&self.library)?.average_mass() * value.mean)
+            })
+            .sum::<Option<f64>>()?;
+        (sum != 0.0).then(|| mass / sum)
     }
 
     pub fn bound(self, bound: Bound<f64>) -> Self {
@@ -27,76 +99,312 @@ impl Output {
     }
 
     pub fn configure(self, config: &Config) -> Output {
-        self.bound(config.bound)
-            .map(|tags| match config.composition {
-                Some(Composition::PositionalSpecie) => tags
-                    .into_iter()
-                    .flat_map(|tag| [tag.clone(), tag.reverse()])
-                    .collect(),
-                _ => tags,
-            })
+        let library = self.library.clone();
+        let output = self.bound(config.bound);
+        let output = match config.composition {
+            Some(Composition::PositionalSpecie) => output.map(|tags| {
+                tags.into_iter()
+                    .flat_map(|tag| [tag.clone(), tag.mirror()])
+                    .collect()
+            }),
+            Some(Composition::PositionalType) => {
+                output.group(|tag| tag.positional_type(&library).map(Group::PositionalType))
+            }
+            Some(Composition::Specie) => {
+                let fatty_acids = output.fatty_acids.clone();
+                output.group(|tag| Some(Group::Specie(tag.sorted(&fatty_acids))))
+            }
+            Some(Composition::Type) => output.group(|tag| {
+                let mut saturations = tag.positional_type(&library)?;
+                saturations.sort();
+                Some(Group::Type(saturations))
+            }),
+            Some(Composition::Sum) => output.group(|tag| {
+                let (carbons, bonds) = tag.sum(&library)?;
+                Some(Group::Sum {
+                    layout: tag.layout(),
+                    carbons,
+                    bonds,
+                })
+            }),
+            Some(Composition::Carbons) => output.group(|tag| {
+                let (carbons, _) = tag.sum(&library)?;
+                Some(Group::Carbons(carbons))
+            }),
+            Some(Composition::Ecn) => output.group(|tag| {
+                let (carbons, bonds) = tag.sum(&library)?;
+                Some(Group::Ecn(carbons.checked_sub(bonds.checked_mul(2)?)?))
+            }),
+            None => output,
+        };
+        let matcher = config.pattern.matcher(&library);
+        output
             .filter(|tags, _| {
                 for tag in tags {
-                    if tag == config.pattern {
+                    if tag == matcher {
                         return true;
                     }
                 }
                 false
             })
+            .filter(|tags, value| {
+                config
+                    .query
+                    .as_ref()
+                    .is_none_or(|query| query.matches(tags, value, &matcher))
+            })
             .sort(config.sort.unwrap_or_default())
     }
 
+    /// Values of the grouped tags in the specie, the enantiomers (ABC and CBA
+    /// of the triacylglycerols) of the specie group are merged. Must be called on the output
+    /// before configure.
+    pub fn breakdown(&self, specie: &Specie, tags: &Tags) -> BTreeMap<Tag, Value> {
+        let mut breakdown = BTreeMap::new();
+        if let Some(values) = self.species.get(specie) {
+            for tag in tags {
+                let value = values
+                    .get(&once(tag.clone()).collect::<Tags>())
+                    .cloned()
+                    .unwrap_or_default();
+                let key = match tags.group() {
+                    Some(Group::Specie(_)) => tags.enantiomer(tag),
+                    _ => tag.clone(),
+                };
+                *breakdown.entry(key).or_default() += value;
+            }
+        }
+        breakdown
+    }
+
+    /// Convert the values from mole percent to weight percent by the average
+    /// mass of the triacylglycerols, the replicates are normalized separately
+    /// and the propagated terms with the terms of the normalizing sum. Returns
+    /// `None` if any mass is unknown.
+    pub fn weight(&self) -> Option<Self> {
+        let mut species = IndexMap::new();
+        for (specie, values) in &self.species {
+            let masses = values
+                .keys()
+                .map(|tags| {
+                    let tag = tags.first()?;
+                    Some(tag.formula(&self.library)?.average_mass())
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let sum = values
+                .values()
+                .zip(&masses)
+                .map(|(value, mass)| value.mean * mass)
+                .sum::<f64>();
+            let count = values
+                .values()
+                .map(|value| value.replicates.len())
+                .max()
+                .unwrap_or_default();
+            let sums = (0..count)
+                .map(|index| {
+                    values
+                        .values()
+                        .zip(&masses)
+                        .map(|(value, mass)| value.replicates.get(index).unwrap_or(&0.0) * mass)
+                        .sum::<f64>()
+                })
+                .collect::<Vec<_>>();
+            // Terms of the sum, the sum is correlated with every value
+            let count = values
+                .values()
+                .map(|value| value.terms.len())
+                .max()
+                .unwrap_or_default();
+            let terms = (0..count)
+                .map(|index| {
+                    values
+                        .values()
+                        .zip(&masses)
+                        .map(|(value, mass)| value.terms.get(index).unwrap_or(&0.0) * mass)
+                        .sum::<f64>()
+                })
+                .collect::<Vec<_>>();
+            let values = values
+                .iter()
+                .zip(masses)
+                .map(|((tags, value), mass)| {
+                    let value = if !value.terms.is_empty() {
+                        // d(m x / S) = m / S (dx - x dS / S)
+                        Value::propagated(
+                            100.0 * value.mean * mass / sum,
+                            terms
+                                .iter()
+                                .enumerate()
+                                .map(|(index, term)| {
+                                    let own = value.terms.get(index).unwrap_or(&0.0);
+                                    100.0 * mass / sum * (own - value.mean * term / sum)
+                                })
+                                .collect(),
+                        )
+                    } else if value.replicates.is_empty() {
+                        value.clone() * (100.0 * mass / sum)
+                    } else {
+                        Value::from_replicates(
+                            value
+                                .replicates
+                                .iter()
+                                .zip(&sums)
+                                .map(|(replicate, sum)| 100.0 * replicate * mass / sum)
+                                .collect(),
+                        )
+                    };
+                    (tags.clone(), value)
+                })
+                .collect();
+            species.insert(specie.clone(), values);
+        }
+        Some(Output::new(species, self.library.clone()).ordered(&self.fatty_acids))
+    }
+
     pub fn filter<F: Fn(&Tags, f64) -> bool>(mut self, f: F) -> Self {
-        self.0.retain(|_, value| {
-            value.retain(|key, value| f(key, *value));
+        self.species.retain(|_, value| {
+            value.retain(|key, value| f(key, value.mean));
             !value.is_empty()
         });
         self
     }
 
+    /// Group the tags, the tags of a group are collected over all species, so
+    /// the groups are equal for every specie. The tags without a group are kept
+    /// as is.
+    pub fn group<F: Fn(&Tag) -> Option<Group>>(self, f: F) -> Self {
+        let mut groups = HashMap::<_, BTreeSet<_>>::new();
+        for tag in self.tags().into_iter().flatten() {
+            if let Some(group) = f(tag) {
+                groups.entry(group).or_default().insert(tag.clone());
+            }
+        }
+        self.map(|tags| match tags.first().and_then(&f) {
+            Some(group) => {
+                let tags = groups[&group].clone();
+                Tags::grouped(group, tags)
+            }
+            None => tags,
+        })
+    }
+
     pub fn map<F: Fn(Tags) -> Tags>(self, f: F) -> Self {
-        Output(
-            self.0
+        Output::new(
+            self.species
                 .into_iter()
                 .map(move |(key, value)| {
                     let value = value
                         .into_iter()
                         .fold(IndexMap::new(), |mut map, (key, value)| {
-                            map.entry(f(key))
-                                .and_modify(|accumulator| *accumulator += value)
-                                .or_insert(value);
+                            *map.entry(f(key)).or_default() += value;
                             map
                         });
                     (key, value)
                 })
                 .collect(),
+            self.library,
         )
+        .ordered(&self.fatty_acids)
     }
 
     pub fn sort(mut self, sort: Sort) -> Self {
-        for value in self.0.values_mut() {
+        for value in self.species.values_mut() {
             match sort {
                 Sort::Key => value.sort_keys(),
-                Sort::Value => value.sort_by(|_, a, _, b| a.total_cmp(b)),
+                Sort::Value => value.sort_by(|_, a, _, b| a.mean.total_cmp(&b.mean)),
             }
         }
         self
     }
 
+    /// Tab-separated values: tags in rows, species in columns. The standard
+    /// deviations follow in separate columns if any is known, the coefficients
+    /// of variation and the replicate values if the specie has replicates.
+    pub fn tsv(&self, notation: &Notation) -> String {
+        let species = self.species();
+        let deviations = self
+            .species
+            .values()
+            .flat_map(IndexMap::values)
+            .any(|value| value.deviation.is_some());
+        let replicates = species
+            .iter()
+            .map(|&specie| {
+                self.species[specie]
+                    .values()
+                    .map(|value| value.replicates.len())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        let mut tsv = String::new();
+        for (specie, &replicates) in species.iter().zip(&replicates) {
+            let taxonomy = specie.taxonomy(".");
+            tsv.push('\t');
+            tsv += &taxonomy;
+            if deviations {
+                tsv += &format!("\t{taxonomy} SD");
+            }
+            if replicates > 0 {
+                tsv += &format!("\t{taxonomy} CV");
+                for index in 1..=replicates {
+                    tsv += &format!("\t{taxonomy} #{index}");
+                }
+            }
+        }
+        tsv.push('\n');
+        for tags in self.tags() {
+            tsv += &notation.tags(tags, &self.library, false);
+            for (&specie, &replicates) in species.iter().zip(&replicates) {
+                let value = self.species[specie].get(tags);
+                tsv.push('\t');
+                if let Some(value) = value {
+                    tsv += &value.mean.to_string();
+                }
+                if deviations {
+                    tsv.push('\t');
+                    if let Some(deviation) = value.and_then(|value| value.deviation) {
+                        tsv += &deviation.to_string();
+                    }
+                }
+                if replicates > 0 {
+                    tsv.push('\t');
+                    if let Some(cv) = value.and_then(Value::coefficient_of_variation) {
+                        tsv += &cv.to_string();
+                    }
+                    for index in 0..replicates {
+                        tsv.push('\t');
+                        if let Some(replicate) = value.and_then(|value| value.replicates.get(index))
+                        {
+                            tsv += &replicate.to_string();
+                        }
+                    }
+                }
+            }
+            tsv.push('\n');
+        }
+        tsv
+    }
+
     fn species(&self) -> Vec<&Specie> {
-        self.0.keys().collect()
+        self.species.keys().collect()
     }
 
     fn tags(&self) -> Vec<&Tags> {
-        self.0.values().flat_map(IndexMap::keys).unique().collect()
+        self.species
+            .values()
+            .flat_map(IndexMap::keys)
+            .unique()
+            .collect()
     }
 }
 
 impl Deref for Output {
-    type Target = IndexMap<Specie, IndexMap<Tags, f64>>;
+    type Target = IndexMap<Specie, IndexMap<Tags, Value>>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.species
     }
 }
 
@@ -121,15 +429,113 @@ impl Deref for Output {
 // }
 
 impl<'a> IntoIterator for &'a Output {
-    type Item = (&'a Specie, &'a IndexMap<Tags, f64>);
+    type Item = (&'a Specie, &'a IndexMap<Tags, Value>);
 
-    type IntoIter = Iter<'a, Specie, IndexMap<Tags, f64>>;
+    type IntoIter = Iter<'a, Specie, IndexMap<Tags, Value>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter()
+        self.species.iter()
     }
 }
 
+/// Breakdown of the grouped tags of the specie
+fn breakdown(ui: &mut Ui, output: &Output, specie: &Specie, tags: &Tags, config: &Config) {
+    Grid::new("breakdown").striped(true).show(ui, |ui| {
+        for (tag, value) in output.breakdown(specie, tags) {
+            ui.label(config.notation.tag(&tag, output.library()));
+            ui.label(format!("{value:.4}%"));
+            ui.end_row();
+        }
+    });
+}
+
+/// Statistics of the value: mean, standard deviation, coefficient of variation
+/// and the replicate values
+fn statistics(ui: &mut Ui, value: &Value) {
+    Grid::new("statistics").striped(true).show(ui, |ui| {
+        ui.label("Mean");
+        ui.label(format!("{}%", value.mean));
+        ui.end_row();
+        if let Some(deviation) = value.deviation {
+            ui.label("SD");
+            ui.label(format!("{deviation}%"));
+            ui.end_row();
+        }
+        if let Some(cv) = value.coefficient_of_variation() {
+            ui.label("CV");
+            ui.label(format!("{cv:.2}%"));
+            ui.end_row();
+        }
+        for (index, replicate) in value.replicates.iter().enumerate() {
+            ui.label(format!("#{}", index + 1));
+            ui.label(format!("{replicate:.4}%"));
+            ui.end_row();
+        }
+    });
+}
+
+/// Difference of the triacylglycerols of the other output from the output, the
+/// outputs of a specie each are configured together so the groups are equal
+pub fn difference(ui: &mut Ui, mut output: Output, other: Output, config: &Config) {
+    output.extend(other);
+    let output = output.configure(config);
+    let (first, second) = match output.species()[..] {
+        [first, second] => (first, second),
+        _ => return,
+    };
+    let rows = output[first]
+        .keys()
+        .chain(output[second].keys())
+        .unique()
+        .map(|tags| {
+            let first = output[first].get(tags).map_or(0.0, |value| value.mean);
+            let second = output[second].get(tags).map_or(0.0, |value| value.mean);
+            (tags, first, second)
+        })
+        .collect::<Vec<_>>();
+    ScrollArea::vertical().show(ui, |ui| {
+        Grid::new("difference").striped(true).show(ui, |ui| {
+            ui.label(config.output_unit.name());
+            ui.label(first.to_string());
+            ui.label(second.to_string());
+            ui.label("Δ");
+            ui.end_row();
+            for &(tags, first, second) in &rows {
+                ui.label(config.notation.tags(tags, output.library(), true));
+                ui.label(format!("{first:.4}%"));
+                ui.label(format!("{second:.4}%"));
+                ui.label(format!("{:+.4}", second - first));
+                ui.end_row();
+            }
+            ui.label(RichText::new("∑ |Δ|").heading());
+            ui.label("");
+            ui.label("");
+            let sum = rows
+                .iter()
+                .map(|(_, first, second)| (second - first).abs())
+                .sum::<f64>();
+            ui.label(format!("{sum:.4}"));
+            ui.end_row();
+        });
+    });
+}
+
+/// Names of the fatty acids of the tags
+fn names(ui: &mut Ui, library: &Library, tags: &Tags) {
+    Grid::new("names").striped(true).show(ui, |ui| {
+        for fatty_acid in tags.iter().flat_map(|tag| tag.iter()).unique() {
+            ui.label(fatty_acid);
+            ui.label(library.name(fatty_acid).unwrap_or_default());
+            if let Some(structure) = library.fatty_acid(fatty_acid) {
+                ui.label(structure.to_string());
+            }
+            ui.end_row();
+        }
+    });
+}
+
+mod lipolysis;
 mod list;
 mod plot;
 mod table;
+mod value;
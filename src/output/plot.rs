@@ -1,10 +1,16 @@
-use crate::{utils::FloatExt, Config, Output};
+use crate::{config::Composition, tag::Group, utils::FloatExt, Config, Output};
 use egui::{
-    plot::{Bar, BarChart, Legend, LinkedAxisGroup, Plot as EguiPlot},
+    plot::{Bar, BarChart, Legend, Line, LinkedAxisGroup, Plot as EguiPlot, PlotPoints},
     ScrollArea, Ui,
 };
 use serde::{Deserialize, Serialize};
-use std::ops::RangeInclusive;
+use std::{f64::consts::TAU, ops::RangeInclusive};
+
+/// Chromatogram peak standard deviation in ECN units
+const WIDTH: f64 = 0.25;
+
+/// Chromatogram resolution in ECN units
+const STEP: f64 = 0.05;
 
 /// Plot UI
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -40,11 +46,13 @@ impl Plot {
             .show(ui, |plot_ui| {
                 let mut offsets = vec![0.0; species.len()];
                 for &tags in &tags {
+                    let label = self.config.notation.tags(tags, configured.library(), false);
                     let mut bars = Vec::new();
                     for (index, &specie) in species.iter().enumerate() {
-                        if let Some(&value) = configured[specie].get(tags) {
+                        if let Some(value) = configured[specie].get(tags) {
+                            let value = value.mean;
                             let mut bar = Bar::new(1.0 + index as f64, value)
-                                .name(format!("{specie}\n{tags}"));
+                                .name(format!("{specie}\n{label}"));
                             if self.stacked {
                                 bar = bar.base_offset(offsets[index]);
                             }
@@ -52,7 +60,7 @@ impl Plot {
                             offsets[index] += value;
                         }
                     }
-                    let chart = BarChart::new(bars).width(0.75).name(tags.to_string());
+                    let chart = BarChart::new(bars).width(0.75).name(label);
                     plot_ui.bar_chart(chart);
                 }
             });
@@ -107,6 +115,7 @@ impl Plot {
             .auto_shrink([false; 2])
             .show(ui, |ui| {
                 for &tags in &tags {
+                    let label = self.config.notation.tags(tags, configured.library(), false);
                     EguiPlot::new(tags)
                         .height(256.0)
                         .data_aspect(1.0)
@@ -117,13 +126,13 @@ impl Plot {
                                 .iter()
                                 .enumerate()
                                 .filter_map(|(index, &specie)| {
-                                    let &value = configured[specie].get(tags)?;
+                                    let value = configured[specie].get(tags)?.mean;
                                     let bar = Bar::new(1.0 + index as f64, value)
-                                        .name(format!("{specie}\n{tags}"));
+                                        .name(format!("{specie}\n{label}"));
                                     Some(bar)
                                 })
                                 .collect();
-                            let chart = BarChart::new(bars).width(0.75).name(tags.to_string());
+                            let chart = BarChart::new(bars).width(0.75).name(&label);
                             ui.bar_chart(chart);
                             // for (index, &specie) in species.iter().enumerate() {
                             //     let mut bars = Vec::new();
@@ -139,7 +148,62 @@ impl Plot {
             });
     }
 
+    /// Simulated RP-HPLC chromatogram: a gaussian peak of the TAG area per ECN
+    fn chromatogram(&mut self, ui: &mut Ui) {
+        let configured = self.output.clone().configure(&self.config);
+        let peaks = configured
+            .iter()
+            .map(|(specie, value)| {
+                let peaks = value
+                    .iter()
+                    .filter_map(|(tags, value)| match tags.group()? {
+                        &Group::Ecn(ecn) => Some((ecn as f64, value.mean)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                (specie, peaks)
+            })
+            .collect::<Vec<_>>();
+        let ecns = peaks
+            .iter()
+            .flat_map(|(_, peaks)| peaks)
+            .map(|&(ecn, _)| ecn);
+        let min = ecns.clone().fold(f64::INFINITY, f64::min) - 4.0 * WIDTH;
+        let max = ecns.fold(f64::NEG_INFINITY, f64::max) + 4.0 * WIDTH;
+        EguiPlot::new("chromatogram")
+            .x_axis_formatter(|x, _range: &RangeInclusive<f64>| {
+                if x.is_approx_integer() {
+                    return format!("ECN {x:.0}");
+                }
+                String::new()
+            })
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                if min > max {
+                    return;
+                }
+                for (specie, peaks) in &peaks {
+                    let count = ((max - min) / STEP) as usize;
+                    let points = (0..=count)
+                        .map(|index| {
+                            let x = min + index as f64 * STEP;
+                            let y = peaks
+                                .iter()
+                                .map(|&(ecn, value)| value * gaussian(x - ecn))
+                                .sum();
+                            [x, y]
+                        })
+                        .collect::<PlotPoints>();
+                    plot_ui.line(Line::new(points).name(specie.to_string()));
+                }
+            });
+    }
+
     pub fn ui(&mut self, ui: &mut Ui) {
+        if let Some(Composition::Ecn) = self.config.composition {
+            self.chromatogram(ui);
+            return;
+        }
         // self.direct(ui);
         self.splited(ui);
 
@@ -149,3 +213,8 @@ impl Plot {
         // }
     }
 }
+
+/// Normal distribution density with the peak width
+fn gaussian(x: f64) -> f64 {
+    (-0.5 * (x / WIDTH).powi(2)).exp() / (WIDTH * TAU.sqrt())
+}
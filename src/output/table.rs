@@ -1,6 +1,11 @@
-use crate::{Config, Output};
+use super::{breakdown, names, statistics};
+use crate::{
+    experimental::{get, Experimental},
+    Config, Output,
+};
 use egui::{Grid, Layout, ScrollArea, TextStyle, Ui};
 use egui_extras::{Column, TableBuilder};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 /// Table UI
@@ -8,6 +13,8 @@ use serde::{Deserialize, Serialize};
 pub struct Table {
     pub output: Output,
     pub config: Config,
+    /// Experimental values side by side with the calculated
+    pub experimental: Option<Experimental>,
 }
 
 impl Table {
@@ -16,6 +23,13 @@ impl Table {
         let configured = self.output.clone().configure(&self.config);
         let species = configured.species();
         let tags = configured.tags();
+        let experimental = self.experimental.as_ref().map(|experimental| {
+            (
+                experimental,
+                experimental.configure(&self.output, &self.config),
+            )
+        });
+        let columns = if experimental.is_some() { 2 } else { 0 };
         ScrollArea::both().auto_shrink([false; 2]).show(ui, |ui| {
             TableBuilder::new(ui)
                 .resizable(true)
@@ -23,9 +37,14 @@ impl Table {
                 .striped(true)
                 .cell_layout(Layout::centered_and_justified(egui::Direction::LeftToRight))
                 .column(Column::auto().resizable(true))
-                .columns(Column::remainder().at_least(4.0 * size), species.len())
+                .columns(
+                    Column::remainder().at_least(4.0 * size),
+                    species.len() + columns,
+                )
                 .header(size, |mut row| {
-                    row.col(|_| {});
+                    row.col(|ui| {
+                        ui.label(self.config.output_unit.name());
+                    });
                     for &specie in &species {
                         row.col(|ui| {
                             ui.heading(specie.to_string()).on_hover_ui(|ui| {
@@ -44,8 +63,10 @@ impl Table {
                                     ui.label(format!("{major}"));
                                     ui.label(format!("{sum}"));
                                     ui.end_row();
-                                    let sum = triglycerides.values().sum::<f64>();
-                                    let major = filtered.values().sum::<f64>();
+                                    let sum =
+                                        triglycerides.values().map(|value| value.mean).sum::<f64>();
+                                    let major =
+                                        filtered.values().map(|value| value.mean).sum::<f64>();
                                     let minor = sum - major;
                                     ui.label(format!("{minor:.1}%"));
                                     ui.label(format!("{major:.1}%"));
@@ -55,21 +76,81 @@ impl Table {
                             });
                         });
                     }
+                    if let Some((experimental, _)) = &experimental {
+                        row.col(|ui| {
+                            ui.heading("Experimental").on_hover_ui(|ui| {
+                                experimental.statistics(&self.output, &self.config).ui(ui);
+                            });
+                        });
+                        row.col(|ui| {
+                            ui.heading("Δ").on_hover_text(format!(
+                                "Experimental - calculated ({})",
+                                experimental.specie
+                            ));
+                        });
+                    }
                 })
                 .body(|mut body| {
                     for &tags in &tags {
                         body.row(size, |mut row| {
                             row.col(|ui| {
-                                if self.config.composition.is_none() {
-                                    ui.label(tags.to_string());
+                                let notation = self.config.notation;
+                                let library = self.output.library();
+                                let response = ui.label(notation.tags(
+                                    tags,
+                                    library,
+                                    self.config.composition.is_some(),
+                                ));
+                                if tags.group().is_some() {
+                                    response.on_hover_text(
+                                        tags.iter()
+                                            .map(|tag| notation.tag(tag, library))
+                                            .join(", "),
+                                    );
                                 } else {
-                                    ui.label(format!("{tags:#}"));
+                                    response.on_hover_ui(|ui| {
+                                        names(ui, self.output.library(), tags);
+                                    });
                                 }
                             });
                             for &specie in &species {
                                 row.col(|ui| {
-                                    if let Some(value) = configured.0[specie].get(tags) {
-                                        ui.label(format!("{value:.4}%"));
+                                    if let Some(value) = configured[specie].get(tags) {
+                                        let response = ui.label(format!("{value:.4}%"));
+                                        if tags.len() > 1 {
+                                            response.on_hover_ui(|ui| {
+                                                breakdown(
+                                                    ui,
+                                                    &self.output,
+                                                    specie,
+                                                    tags,
+                                                    &self.config,
+                                                );
+                                            });
+                                        } else {
+                                            response.on_hover_ui(|ui| statistics(ui, value));
+                                        }
+                                    } else {
+                                        ui.label("-");
+                                    }
+                                });
+                            }
+                            if let Some((experimental, values)) = &experimental {
+                                let measured = get(values, tags);
+                                let calculated = configured
+                                    .get(&experimental.specie)
+                                    .and_then(|values| values.get(tags))
+                                    .map_or(0.0, |value| value.mean);
+                                row.col(|ui| {
+                                    if let Some(measured) = measured {
+                                        ui.label(format!("{measured:.4}%"));
+                                    } else {
+                                        ui.label("-");
+                                    }
+                                });
+                                row.col(|ui| {
+                                    if let Some(measured) = measured {
+                                        ui.label(format!("{:+.4}", measured - calculated));
                                     } else {
                                         ui.label("-");
                                     }
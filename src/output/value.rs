@@ -0,0 +1,165 @@
+use crate::utils::FloatExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    iter::Sum,
+    mem::take,
+    ops::{Add, AddAssign, Mul},
+};
+
+/// Value with the standard deviation, either propagated from the input or
+/// calculated from the replicate values
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Value {
+    pub mean: f64,
+    pub deviation: Option<f64>,
+    pub replicates: Vec<f64>,
+    /// Propagated terms of the deviation, the deviation is the root of the
+    /// sum of their squares: the partial derivatives by the input values times
+    /// the input deviations, or the centered Monte Carlo draws over the root of
+    /// the count less one. The terms of the values of the same input are
+    /// correlated.
+    #[serde(skip)]
+    pub terms: Vec<f64>,
+}
+
+impl Value {
+    pub fn new(mean: f64, deviation: Option<f64>) -> Self {
+        Self {
+            mean,
+            deviation,
+            replicates: Vec::new(),
+            terms: Vec::new(),
+        }
+    }
+
+    /// Value with the deviation of the propagated terms
+    pub fn propagated(mean: f64, terms: Vec<f64>) -> Self {
+        let deviation = terms.iter().map(|term| term.powi(2)).sum::<f64>().sqrt();
+        Self {
+            mean,
+            deviation: Some(deviation),
+            replicates: Vec::new(),
+            terms,
+        }
+    }
+
+    /// Mean and sample standard deviation of the replicate values
+    pub fn from_replicates(replicates: Vec<f64>) -> Self {
+        let count = replicates.len() as f64;
+        let mean = replicates.iter().sum::<f64>() / count.max(1.0);
+        let deviation = (replicates.len() > 1).then(|| {
+            let squares = replicates
+                .iter()
+                .map(|replicate| (replicate - mean).powi(2))
+                .sum::<f64>();
+            (squares / (count - 1.0)).sqrt()
+        });
+        Self {
+            mean,
+            deviation,
+            replicates,
+            terms: Vec::new(),
+        }
+    }
+
+    /// Coefficient of variation in percent
+    pub fn coefficient_of_variation(&self) -> Option<f64> {
+        let deviation = self.deviation?;
+        (self.mean != 0.0).then(|| 100.0 * deviation / self.mean)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(mean: f64) -> Self {
+        Self::new(mean, None)
+    }
+}
+
+/// The replicate values are added replicate by replicate, a value without the
+/// replicates is the same in every replicate. The propagated terms are added
+/// term by term, so the sum keeps the covariances of the values of the same
+/// input. Otherwise the deviations are added as of independent values.
+impl Add for Value {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let count = self.replicates.len().max(rhs.replicates.len());
+        if count > 0 {
+            let replicates = |value: &Self| {
+                let mut replicates = value.replicates.clone();
+                replicates.resize(count, value.mean);
+                replicates
+            };
+            return Self::from_replicates(
+                replicates(&self)
+                    .into_iter()
+                    .zip(replicates(&rhs))
+                    .map(|(lhs, rhs)| lhs + rhs)
+                    .collect(),
+            );
+        }
+        // A value without the deviation has no terms
+        let propagated = |value: &Self| {
+            !value.terms.is_empty() || value.deviation.unwrap_or_default().is_approx_zero()
+        };
+        if (!self.terms.is_empty() || !rhs.terms.is_empty())
+            && propagated(&self)
+            && propagated(&rhs)
+        {
+            let count = self.terms.len().max(rhs.terms.len());
+            let terms = (0..count)
+                .map(|index| {
+                    self.terms.get(index).unwrap_or(&0.0) + rhs.terms.get(index).unwrap_or(&0.0)
+                })
+                .collect();
+            return Self::propagated(self.mean + rhs.mean, terms);
+        }
+        let deviation = match (self.deviation, rhs.deviation) {
+            (None, None) => None,
+            (lhs, rhs) => Some(lhs.unwrap_or_default().hypot(rhs.unwrap_or_default())),
+        };
+        Self::new(self.mean + rhs.mean, deviation)
+    }
+}
+
+impl AddAssign for Value {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = take(self) + rhs;
+    }
+}
+
+impl Mul<f64> for Value {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self {
+            mean: self.mean * rhs,
+            deviation: self.deviation.map(|deviation| deviation * rhs.abs()),
+            replicates: self
+                .replicates
+                .into_iter()
+                .map(|replicate| replicate * rhs)
+                .collect(),
+            terms: self.terms.into_iter().map(|term| term * rhs).collect(),
+        }
+    }
+}
+
+impl Sum for Value {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// Mean ± standard deviation, the precision applies to both
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        write!(f, "{:.precision$}", self.mean)?;
+        if let Some(deviation) = self.deviation {
+            write!(f, " ± {deviation:.precision$}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,498 @@
+use crate::{
+    fatty_acid::Class,
+    tag::{Matcher, Wildcard},
+    Tag, Tags,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    error,
+    fmt::{self, Display, Formatter},
+    ops::Range,
+    str::FromStr,
+};
+
+/// Query of the acylglycerols
+///
+/// Conditions joined by `and`, `or`, `not` and the parentheses, the keywords
+/// are case insensitive:
+/// - `sn1 = P`, `sn2 != L`, `sn1 in {P, St}`: the fatty acid of the position,
+///   a set, a class (`SFA`, `MUFA`, `PUFA`) or a group of the pattern (`@a`);
+/// - `contains Ln`: the fatty acid at any position;
+/// - `CN >= 52`, `DB < 5`, `ECN = 46`: the total carbon number, the double
+///   bond count and the equivalent carbon number;
+/// - `value > 1%`: the value of the output unit, percent.
+///
+/// For example `sn2 = L and contains Ln and not sn1 in {P, St}`. The grouped
+/// tags match if any tag of the group matches.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    /// Fatty acid of the position (0 for sn-1), the tags without the position
+    /// do not match
+    Position {
+        position: usize,
+        wildcard: Wildcard,
+    },
+    /// Fatty acid of any position
+    Contains(Wildcard),
+    Property {
+        property: Property,
+        comparison: Comparison,
+        value: f64,
+    },
+}
+
+impl Query {
+    pub fn matches(&self, tags: &Tags, value: f64, matcher: &Matcher) -> bool {
+        tags.iter().any(|tag| self.tag(tag, value, matcher))
+    }
+
+    fn tag(&self, tag: &Tag, value: f64, matcher: &Matcher) -> bool {
+        match self {
+            Self::And(left, right) => {
+                left.tag(tag, value, matcher) && right.tag(tag, value, matcher)
+            }
+            Self::Or(left, right) => {
+                left.tag(tag, value, matcher) || right.tag(tag, value, matcher)
+            }
+            Self::Not(query) => !query.tag(tag, value, matcher),
+            Self::Position { position, wildcard } => tag
+                .get(*position)
+                .is_some_and(|fatty_acid| wildcard.matches(fatty_acid, matcher)),
+            Self::Contains(wildcard) => tag
+                .iter()
+                .any(|fatty_acid| wildcard.matches(fatty_acid, matcher)),
+            Self::Property {
+                property,
+                comparison,
+                value: expected,
+            } => {
+                let actual = match property {
+                    Property::Value => value,
+                    _ => match tag.sum(matcher.library()) {
+                        Some((carbons, bonds)) => match property {
+                            Property::Carbons => carbons as f64,
+                            Property::Bonds => bonds as f64,
+                            _ => carbons as f64 - 2.0 * bonds as f64,
+                        },
+                        None => return false,
+                    },
+                };
+                comparison.compare(actual, *expected)
+            }
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let query = parser.or()?;
+        match parser.peek() {
+            Some(token) => Err(ParseError::new(token.span.clone(), "Unexpected token")),
+            None => Ok(query),
+        }
+    }
+}
+
+/// Property of the acylglycerol
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Property {
+    /// Total carbon number
+    Carbons,
+    /// Total double bond count
+    Bonds,
+    /// Equivalent carbon number (CN - 2 * DB)
+    Ecn,
+    Value,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn compare(&self, left: f64, right: f64) -> bool {
+        match self {
+            Self::Equal => left == right,
+            Self::NotEqual => left != right,
+            Self::Less => left < right,
+            Self::LessOrEqual => left <= right,
+            Self::Greater => left > right,
+            Self::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+/// Error of the query parsing with the byte range of the wrong text
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(span: Range<usize>, message: impl ToString) -> Self {
+        Self {
+            span,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Symbols of the query, the longest first
+const SYMBOLS: [&str; 12] = [
+    "!=", "<=", ">=", "(", ")", "{", "}", ",", "=", "<", ">", "%",
+];
+
+#[derive(Clone, Debug)]
+struct Token<'a> {
+    text: &'a str,
+    span: Range<usize>,
+}
+
+impl Token<'_> {
+    fn is(&self, keyword: &str) -> bool {
+        self.text.eq_ignore_ascii_case(keyword)
+    }
+}
+
+/// Recursive descent parser of the query
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    index: usize,
+    /// Length of the text, the span of the missing tokens
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        let mut rest = text.trim_start();
+        while !rest.is_empty() {
+            let start = text.len() - rest.len();
+            let length = match SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+                Some(symbol) => symbol.len(),
+                None => rest
+                    .find(|c: char| {
+                        c.is_whitespace() || c == '@' || SYMBOLS.iter().any(|s| s.starts_with(c))
+                    })
+                    .map_or(rest.len(), |index| index.max(1)),
+            };
+            tokens.push(Token {
+                text: &rest[..length],
+                span: start..start + length,
+            });
+            rest = rest[length..].trim_start();
+        }
+        Self {
+            tokens,
+            index: 0,
+            end: text.len(),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Token<'a>, ParseError> {
+        let token =
+            self.tokens.get(self.index).cloned().ok_or_else(|| {
+                ParseError::new(self.end..self.end, format!("Expected {expected}"))
+            })?;
+        self.index += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(token) if token.is(keyword) => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, keyword: &str) -> Result<(), ParseError> {
+        let token = self.next(&format!("`{keyword}`"))?;
+        if !token.is(keyword) {
+            return Err(ParseError::new(token.span, format!("Expected `{keyword}`")));
+        }
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<Query, ParseError> {
+        let mut query = self.and()?;
+        while self.eat("or") {
+            query = Query::Or(Box::new(query), Box::new(self.and()?));
+        }
+        Ok(query)
+    }
+
+    fn and(&mut self) -> Result<Query, ParseError> {
+        let mut query = self.not()?;
+        while self.eat("and") {
+            query = Query::And(Box::new(query), Box::new(self.not()?));
+        }
+        Ok(query)
+    }
+
+    fn not(&mut self) -> Result<Query, ParseError> {
+        if self.eat("not") {
+            return Ok(Query::Not(Box::new(self.not()?)));
+        }
+        if self.eat("(") {
+            let query = self.or()?;
+            self.expect(")")?;
+            return Ok(query);
+        }
+        self.condition()
+    }
+
+    fn condition(&mut self) -> Result<Query, ParseError> {
+        let token = self.next("a condition")?;
+        let position = token
+            .text
+            .get(..2)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("sn"))
+            .and_then(|_| token.text[2..].parse::<usize>().ok())
+            .filter(|&position| position > 0)
+            .map(|position| position - 1);
+        if let Some(position) = position {
+            let operator = self.next("`=`, `!=` or `in`")?;
+            let wildcard = self.wildcard()?;
+            let wildcard = if operator.is("=") || operator.is("in") {
+                wildcard
+            } else if operator.is("!=") {
+                Wildcard::Not(Box::new(wildcard))
+            } else {
+                return Err(ParseError::new(operator.span, "Expected `=`, `!=` or `in`"));
+            };
+            return Ok(Query::Position { position, wildcard });
+        }
+        if token.is("contains") {
+            return Ok(Query::Contains(self.wildcard()?));
+        }
+        let property = if token.is("cn") {
+            Property::Carbons
+        } else if token.is("db") {
+            Property::Bonds
+        } else if token.is("ecn") {
+            Property::Ecn
+        } else if token.is("value") {
+            Property::Value
+        } else {
+            return Err(ParseError::new(token.span, "Expected a condition"));
+        };
+        let operator = self.next("a comparison")?;
+        let comparison = match operator.text {
+            "=" => Comparison::Equal,
+            "!=" => Comparison::NotEqual,
+            "<" => Comparison::Less,
+            "<=" => Comparison::LessOrEqual,
+            ">" => Comparison::Greater,
+            ">=" => Comparison::GreaterOrEqual,
+            _ => return Err(ParseError::new(operator.span, "Expected a comparison")),
+        };
+        let number = self.next("a number")?;
+        let value = number
+            .text
+            .parse()
+            .map_err(|_| ParseError::new(number.span.clone(), "Expected a number"))?;
+        if property == Property::Value {
+            self.eat("%");
+        }
+        Ok(Query::Property {
+            property,
+            comparison,
+            value,
+        })
+    }
+
+    fn wildcard(&mut self) -> Result<Wildcard, ParseError> {
+        if self.eat("{") {
+            let mut set = BTreeSet::new();
+            loop {
+                set.insert(self.fatty_acid()?);
+                let token = self.next("`,` or `}`")?;
+                if token.is("}") {
+                    return Ok(Wildcard::Set(set));
+                }
+                if !token.is(",") {
+                    return Err(ParseError::new(token.span, "Expected `,` or `}`"));
+                }
+            }
+        }
+        if self.eat("@") {
+            return Ok(Wildcard::Group(self.fatty_acid()?));
+        }
+        let fatty_acid = self.fatty_acid()?;
+        let class = [
+            Class::Saturated,
+            Class::Monounsaturated,
+            Class::Polyunsaturated,
+        ]
+        .into_iter()
+        .find(|class| class.abbreviation() == fatty_acid);
+        Ok(match class {
+            Some(class) => Wildcard::Class(class),
+            None => Wildcard::FattyAcid(fatty_acid),
+        })
+    }
+
+    fn fatty_acid(&mut self) -> Result<String, ParseError> {
+        let token = self.next("a fatty acid")?;
+        if SYMBOLS.contains(&token.text) || token.text == "@" {
+            return Err(ParseError::new(token.span, "Expected a fatty acid"));
+        }
+        Ok(token.text.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fatty_acid::Library, input::Layout, tag::Pattern};
+    use std::iter::once;
+
+    fn parse(text: &str) -> Query {
+        text.parse().unwrap()
+    }
+
+    fn matches(query: &str, tag: &str, value: f64, pattern: &Pattern) -> bool {
+        let tags = once(Tag::new(
+            Layout::Triacylglycerol,
+            tag.chars().map(String::from).collect::<Vec<_>>(),
+        ))
+        .collect::<Tags>();
+        let library = Library::default();
+        parse(query).matches(&tags, value, &pattern.matcher(&library))
+    }
+
+    fn position(position: usize, wildcard: Wildcard) -> Box<Query> {
+        Box::new(Query::Position { position, wildcard })
+    }
+
+    fn fatty_acid(fatty_acid: &str) -> Wildcard {
+        Wildcard::FattyAcid(fatty_acid.to_owned())
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("sn1 = P or sn2 = O and sn3 = L"),
+            Query::Or(
+                position(0, fatty_acid("P")),
+                Box::new(Query::And(
+                    position(1, fatty_acid("O")),
+                    position(2, fatty_acid("L")),
+                )),
+            ),
+        );
+        assert_eq!(
+            parse("(sn1 = P or sn2 = O) and sn3 = L"),
+            Query::And(
+                Box::new(Query::Or(
+                    position(0, fatty_acid("P")),
+                    position(1, fatty_acid("O")),
+                )),
+                position(2, fatty_acid("L")),
+            ),
+        );
+        assert_eq!(
+            parse("not sn1 = P and sn2 = O"),
+            Query::And(
+                Box::new(Query::Not(position(0, fatty_acid("P")))),
+                position(1, fatty_acid("O")),
+            ),
+        );
+        assert_eq!(parse("SN1 = P AND Sn2 = O"), parse("sn1 = P and sn2 = O"));
+    }
+
+    #[test]
+    fn not_equal() {
+        assert_eq!(
+            parse("sn2 != L"),
+            Query::Position {
+                position: 1,
+                wildcard: Wildcard::Not(Box::new(fatty_acid("L"))),
+            },
+        );
+        let pattern = Pattern::default();
+        assert!(matches("sn2 != L", "POP", 0.0, &pattern));
+        assert!(!matches("sn2 != L", "PLP", 0.0, &pattern));
+    }
+
+    #[test]
+    fn wildcards() {
+        let mut pattern = Pattern::default();
+        pattern
+            .groups
+            .insert("a".to_owned(), ["P", "S"].map(String::from).into());
+        assert_eq!(
+            parse("sn1 in {P, S}"),
+            *position(0, Wildcard::Set(["P", "S"].map(String::from).into())),
+        );
+        assert!(matches("sn1 in {P, S}", "SOO", 0.0, &pattern));
+        assert!(!matches("sn1 in {P, S}", "OOO", 0.0, &pattern));
+        assert_eq!(
+            parse("sn3 = @a"),
+            *position(2, Wildcard::Group("a".to_owned()))
+        );
+        assert!(matches("sn3 = @a", "OOS", 0.0, &pattern));
+        assert!(!matches("sn3 = @a", "OSO", 0.0, &pattern));
+        assert_eq!(
+            parse("contains SFA"),
+            Query::Contains(Wildcard::Class(Class::Saturated)),
+        );
+        assert!(matches("contains SFA", "OPO", 0.0, &pattern));
+        assert!(!matches("contains SFA", "OLO", 0.0, &pattern));
+        assert!(matches("sn2 = PUFA", "OLO", 0.0, &pattern));
+    }
+
+    #[test]
+    fn properties() {
+        let pattern = Pattern::default();
+        assert!(matches("CN = 50", "POP", 0.0, &pattern));
+        assert!(matches("DB < 2 and ECN = 48", "POP", 0.0, &pattern));
+        assert!(matches("value > 1%", "POP", 1.5, &pattern));
+        assert!(!matches("value > 1", "POP", 0.5, &pattern));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |text: &str| text.parse::<Query>().unwrap_err();
+        assert_eq!(error("sn1 = P and").span, 11..11);
+        assert_eq!(error("sn1 < P").span, 4..5);
+        assert_eq!(error("cn >= x").span, 6..7);
+        assert_eq!(error("sn1 = P)").span, 7..8);
+        assert_eq!(error("(sn1 = P").message, "Expected `)`");
+        assert_eq!(error("sn1 in {P S}").span, 10..11);
+        assert_eq!(error("foo").span, 0..3);
+    }
+}
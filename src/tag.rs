@@ -1,3 +1,10 @@
+use crate::{
+    fatty_acid::{Class, FattyAcid, Formula, Library, Saturation},
+    input::Layout,
+};
+use anyhow::{ensure, Context, Result};
+use indexmap::IndexMap;
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{
@@ -5,16 +12,66 @@ use std::{
         BTreeSet,
     },
     fmt::{self, Display, Formatter},
+    iter::once,
     ops::{Deref, DerefMut},
 };
 
 /// Tags
 #[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
-pub struct Tags(BTreeSet<Tag>);
+pub struct Tags {
+    group: Option<Group>,
+    tags: BTreeSet<Tag>,
+}
 
 impl Tags {
     pub fn new(tags: BTreeSet<Tag>) -> Self {
-        Self(tags)
+        Self { group: None, tags }
+    }
+
+    pub fn grouped(group: Group, tags: BTreeSet<Tag>) -> Self {
+        Self {
+            group: Some(group),
+            tags,
+        }
+    }
+
+    pub fn group(&self) -> Option<&Group> {
+        self.group.as_ref()
+    }
+
+    /// Isomers of the group, the enantiomers are merged. The isomers of the
+    /// specie group are in the order of its fatty acids (`POL`, `PLO`, `OPL`).
+    pub fn isomers(&self) -> impl Iterator<Item = &Tag> {
+        self.tags
+            .iter()
+            .filter(|&tag| {
+                let mirror = tag.clone().mirror();
+                self.enantiomer(tag) == *tag || !self.tags.contains(&mirror)
+            })
+            .sorted_by_key(|&tag| self.rank(tag))
+    }
+
+    /// Enantiomer of the tag first in the order of the fatty acids of the
+    /// specie group, the lesser one without the specie group
+    pub fn enantiomer(&self, tag: &Tag) -> Tag {
+        let mirror = tag.clone().mirror();
+        match (self.rank(tag), self.rank(&mirror)) {
+            (Some(rank), Some(mirrored)) if mirrored < rank => mirror,
+            (Some(_), Some(_)) => tag.clone(),
+            _ => tag.clone().min(mirror),
+        }
+    }
+
+    /// Indices of the fatty acids of the tag in the specie group, `None`
+    /// without the specie group
+    fn rank(&self, tag: &Tag) -> Option<Vec<usize>> {
+        let group = match &self.group {
+            Some(Group::Specie(group)) => group,
+            _ => return None,
+        };
+        tag.iter()
+            .map(|fatty_acid| group.iter().position(|item| item == fatty_acid))
+            .collect()
     }
 }
 
@@ -22,25 +79,13 @@ impl Deref for Tags {
     type Target = BTreeSet<Tag>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl Display for Tags {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        for (index, tag) in self.0.iter().enumerate() {
-            if index != 0 {
-                f.write_str(", ")?;
-            }
-            write!(f, "{tag}")?;
-        }
-        Ok(())
+        &self.tags
     }
 }
 
 impl FromIterator<Tag> for Tags {
     fn from_iter<T: IntoIterator<Item = Tag>>(iter: T) -> Self {
-        Self(iter.into_iter().collect())
+        Self::new(iter.into_iter().collect())
     }
 }
 
@@ -50,7 +95,7 @@ impl IntoIterator for Tags {
     type IntoIter = IntoIter<Tag>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.tags.into_iter()
     }
 }
 
@@ -60,29 +105,168 @@ impl<'a> IntoIterator for &'a Tags {
     type IntoIter = Iter<'a, Tag>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter()
+        self.tags.iter()
     }
 }
 
-/// 1,2,3-Triacyl-sn-glycerol
+/// Acyl-sn-glycerol, the fatty acids of the positions: sn-1, sn-2, sn-3 of
+/// the triacylglycerols, sn-1, sn-2 of the diacylglycerols and the
+/// phospholipids. The positions are of the glycerol backbone, at most three:
+/// the acyls esterified to the acyl chains (estolides) are not represented.
 #[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct Tag {
-    fatty_acids: [String; 3],
+    fatty_acids: Vec<String>,
+    layout: Layout,
 }
 
 impl Tag {
-    pub fn new(fatty_acids: [String; 3]) -> Self {
-        Self { fatty_acids }
+    pub fn new(layout: Layout, fatty_acids: impl Into<Vec<String>>) -> Self {
+        Self {
+            fatty_acids: fatty_acids.into(),
+            layout,
+        }
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
     }
 
-    pub fn reverse(mut self) -> Self {
-        self.fatty_acids.reverse();
+    /// Enantiomer: sn-1 and sn-3 of the triacylglycerols are swapped, the
+    /// sn-1 and sn-2 positions of the diacylglycerols and the phospholipids
+    /// are not mirrored
+    pub fn mirror(mut self) -> Self {
+        if let Layout::Triacylglycerol = self.layout {
+            self.fatty_acids.swap(0, 2);
+        }
         self
     }
+
+    /// Tag with the fatty acids sorted by the order, the fatty acids not in
+    /// the order are last
+    pub fn sorted(&self, order: &[String]) -> Self {
+        let mut fatty_acids = self.fatty_acids.clone();
+        fatty_acids.sort_by_cached_key(|fatty_acid| {
+            let index = order.iter().position(|item| item == fatty_acid);
+            (index.unwrap_or(order.len()), fatty_acid.clone())
+        });
+        Self::new(self.layout, fatty_acids)
+    }
+
+    /// Total carbon number and double bond count of the acyl chains, `None` if
+    /// the structure is unknown or the counts are out of range
+    pub fn sum(&self, library: &Library) -> Option<(u8, u8)> {
+        self.fatty_acids
+            .iter()
+            .try_fold((0u8, 0u8), |(carbons, bonds), fatty_acid| {
+                let fatty_acid = library.fatty_acid(fatty_acid)?;
+                Some((
+                    carbons.checked_add(fatty_acid.carbons)?,
+                    bonds.checked_add(fatty_acid.bonds)?,
+                ))
+            })
+    }
+
+    /// Formula of the acylglycerol, the glycerol backbone (C3H8O3 - n H2O)
+    /// plus the n fatty acids. `None` for the phospholipids, the head group is
+    /// unknown, and if the counts are out of the range of the formula.
+    pub fn formula(&self, library: &Library) -> Option<Formula> {
+        if let Layout::Phospholipid = self.layout {
+            return None;
+        }
+        let count = self.fatty_acids.len() as u8;
+        if count > 3 {
+            return None;
+        }
+        self.fatty_acids.iter().try_fold(
+            Formula::new(3, 8 - 2 * count, 3 - count),
+            |mut formula, fatty_acid| {
+                let fatty_acid = library.fatty_acid(fatty_acid)?.formula()?;
+                formula.carbons = formula.carbons.checked_add(fatty_acid.carbons)?;
+                formula.hydrogens = formula.hydrogens.checked_add(fatty_acid.hydrogens)?;
+                formula.oxygens = formula.oxygens.checked_add(fatty_acid.oxygens)?;
+                Some(formula)
+            },
+        )
+    }
+
+    /// Tag of the count of the positions in any notation: the concatenated
+    /// abbreviations of the fatty acids (`POL`), dash separated (`P-O-L`)
+    /// or LIPID MAPS (`TG 16:0/18:1/18:2`, `TG 16:0_18:1_18:2`), optionally
+    /// with the `sn-` prefix. The shorthands are replaced by the fatty acids
+    /// of the same structure.
+    pub fn parse(
+        text: &str,
+        layout: Layout,
+        fatty_acids: &[String],
+        library: &Library,
+    ) -> Result<Self> {
+        let positions = layout.positions();
+        let trimmed = text.trim();
+        let trimmed = trimmed
+            .strip_prefix(layout.class())
+            .and_then(|trimmed| trimmed.strip_prefix(' '))
+            .unwrap_or(trimmed)
+            .trim_start();
+        let trimmed = trimmed.strip_prefix("sn-").unwrap_or(trimmed);
+        let parts = if trimmed.contains(['/', '_']) {
+            trimmed.split(['/', '_']).map(str::trim).collect()
+        } else if trimmed.contains('-') {
+            dashed(trimmed, fatty_acids)
+        } else {
+            let mut sorted = fatty_acids.iter().collect::<Vec<_>>();
+            sorted.sort_by_key(|fatty_acid| usize::MAX - fatty_acid.len());
+            split(trimmed, &sorted, positions)
+                .with_context(|| format!("Parse tag ({text}): unknown fatty acids"))?
+        };
+        ensure!(
+            parts.len() == positions,
+            "Parse tag ({text}): expected {positions} fatty acids"
+        );
+        let fatty_acid = |part: &str| {
+            if fatty_acids.iter().any(|fatty_acid| fatty_acid == part) {
+                return part.to_owned();
+            }
+            let parsed = match part.parse::<FattyAcid>() {
+                Ok(parsed) => parsed,
+                Err(_) => return part.to_owned(),
+            };
+            fatty_acids
+                .iter()
+                .find(|fatty_acid| {
+                    library.fatty_acid(fatty_acid).is_some_and(|fatty_acid| {
+                        fatty_acid.carbons == parsed.carbons
+                            && fatty_acid.bonds == parsed.bonds
+                            && (parsed.positions.is_empty()
+                                || fatty_acid
+                                    .positions
+                                    .iter()
+                                    .map(|bond| bond.position)
+                                    .eq(parsed.positions.iter().map(|bond| bond.position)))
+                    })
+                })
+                .cloned()
+                .unwrap_or_else(|| part.to_owned())
+        };
+        Ok(Self::new(
+            layout,
+            parts.into_iter().map(fatty_acid).collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Positional type, the types of the enantiomers (USS and SSU, UUS and
+    /// SUU) are merged
+    pub fn positional_type(&self, library: &Library) -> Option<Vec<Saturation>> {
+        let saturations = |tag: &Tag| {
+            tag.iter()
+                .map(|fatty_acid| library.saturation(fatty_acid))
+                .collect::<Option<Vec<_>>>()
+        };
+        Some(saturations(self)?.min(saturations(&self.clone().mirror())?))
+    }
 }
 
 impl Deref for Tag {
-    type Target = [String; 3];
+    type Target = [String];
 
     fn deref(&self) -> &Self::Target {
         &self.fatty_acids
@@ -91,56 +275,306 @@ impl Deref for Tag {
 
 impl Display for Tag {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}{}{}",
-            self.fatty_acids[0], self.fatty_acids[1], self.fatty_acids[2]
-        )?;
+        for fatty_acid in &self.fatty_acids {
+            f.write_str(fatty_acid)?;
+        }
         Ok(())
     }
 }
 
-impl<'a> FromIterator<&'a String> for Tag {
-    fn from_iter<T: IntoIterator<Item = &'a String>>(iter: T) -> Self {
-        let mut into_iter = iter.into_iter();
-        let one = into_iter.next().cloned().unwrap_or_default();
-        let two = into_iter.next().cloned().unwrap_or_default();
-        let three = into_iter.next().cloned().unwrap_or_default();
-        Self {
-            fatty_acids: [one, two, three],
+/// Split of the dash separated fatty acids, the dash is kept if the part
+/// before it is not a fatty acid and the part through the next dash is one of
+/// the fatty acids or a shorthand (`18:2n-6`, `18:3ω-3`)
+fn dashed<'a>(text: &'a str, fatty_acids: &[String]) -> Vec<&'a str> {
+    let known = |part: &str| {
+        fatty_acids.iter().any(|fatty_acid| fatty_acid == part) || part.parse::<FattyAcid>().is_ok()
+    };
+    let dashes = text
+        .match_indices('-')
+        .map(|(index, _)| index)
+        .chain(once(text.len()))
+        .collect::<Vec<_>>();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for window in dashes.windows(2) {
+        let (dash, next) = (window[0], window[1]);
+        if !known(text[start..dash].trim()) && known(text[start..next].trim()) {
+            continue;
+        }
+        parts.push(text[start..dash].trim());
+        start = dash + 1;
+    }
+    parts.push(text[start..].trim());
+    parts
+}
+
+/// Split of the concatenated abbreviations into the count of the fatty acids,
+/// the longest abbreviations first
+fn split<'a>(text: &'a str, fatty_acids: &[&String], count: usize) -> Option<Vec<&'a str>> {
+    if count == 0 {
+        return text.is_empty().then(Vec::new);
+    }
+    fatty_acids.iter().find_map(|fatty_acid| {
+        let rest = text.strip_prefix(fatty_acid.as_str())?;
+        let mut parts = split(rest, fatty_acids, count - 1)?;
+        parts.insert(0, &text[..fatty_acid.len()]);
+        Some(parts)
+    })
+}
+
+impl PartialEq<Matcher<'_>> for &Tag {
+    fn eq(&self, other: &Matcher) -> bool {
+        self.fatty_acids
+            .iter()
+            .zip(&other.pattern.wildcards)
+            .all(|(fatty_acid, wildcard)| {
+                wildcard
+                    .as_ref()
+                    .is_none_or(|wildcard| wildcard.matches(fatty_acid, other))
+            })
+    }
+}
+
+/// Group of tags
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Group {
+    PositionalType(Vec<Saturation>),
+    /// Type, the saturations are sorted
+    Type(Vec<Saturation>),
+    /// Specie, all the positional isomers, the fatty acids are in the input
+    /// order
+    Specie(Tag),
+    /// Sum composition: total carbon number and double bond count
+    Sum {
+        layout: Layout,
+        carbons: u8,
+        bonds: u8,
+    },
+    /// Total carbon number
+    Carbons(u8),
+    /// Equivalent carbon number (CN - 2 * DB), the order of the RP-HPLC
+    /// elution
+    Ecn(u8),
+}
+
+impl Display for Group {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::PositionalType(saturations) => {
+                for saturation in saturations {
+                    write!(f, "{}", saturation.abbreviation())?;
+                }
+                Ok(())
+            }
+            Self::Type(saturations) => {
+                for saturation in [Saturation::Saturated, Saturation::Unsaturated] {
+                    let count = saturations
+                        .iter()
+                        .filter(|&&item| item == saturation)
+                        .count();
+                    match count {
+                        0 => {}
+                        1 => write!(f, "{}", saturation.abbreviation())?,
+                        _ => write!(f, "{}{count}", saturation.abbreviation())?,
+                    }
+                }
+                Ok(())
+            }
+            Self::Specie(tag) => write!(f, "{tag}"),
+            Self::Sum {
+                layout,
+                carbons,
+                bonds,
+            } => write!(f, "{} {carbons}:{bonds}", layout.class()),
+            Self::Carbons(carbons) => write!(f, "C{carbons}"),
+            Self::Ecn(ecn) => write!(f, "ECN {ecn}"),
         }
     }
 }
 
-impl PartialEq<Pattern> for &Tag {
-    fn eq(&self, other: &Pattern) -> bool {
-        for index in 0..3 {
-            if let Some(fatty_acid) = &other.fatty_acids[index] {
-                if fatty_acid != &self.fatty_acids[index] {
-                    return false;
+/// Notation of the tags
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Notation {
+    pub style: Style,
+    /// `sn-` prefix of the positional tags
+    pub stereo: bool,
+}
+
+impl Notation {
+    /// Positional tag
+    pub fn tag(&self, tag: &Tag, library: &Library) -> String {
+        let prefix = if self.stereo { "sn-" } else { "" };
+        let fatty_acids = self.fatty_acids(tag, library, "/");
+        match self.style {
+            Style::LipidMaps => format!("{} {prefix}{fatty_acids}", tag.layout.class()),
+            _ => format!("{prefix}{fatty_acids}"),
+        }
+    }
+
+    /// Tags, the group is followed by its isomers if `isomers`
+    pub fn tags(&self, tags: &Tags, library: &Library, isomers: bool) -> String {
+        match &tags.group {
+            Some(group) => {
+                let mut text = self.group(group, library);
+                if isomers {
+                    let isomers = tags.isomers().map(|tag| self.tag(tag, library));
+                    text += &format!(" ({})", isomers.format(", "));
                 }
+                text
             }
+            None => tags.iter().map(|tag| self.tag(tag, library)).join(", "),
+        }
+    }
+
+    /// Group, the fatty acids of the specie are without the positions
+    pub fn group(&self, group: &Group, library: &Library) -> String {
+        match group {
+            Group::Specie(tag) => {
+                let fatty_acids = self.fatty_acids(tag, library, "_");
+                match self.style {
+                    Style::LipidMaps => format!("{} {fatty_acids}", tag.layout.class()),
+                    _ => fatty_acids,
+                }
+            }
+            group => group.to_string(),
+        }
+    }
+
+    /// Fatty acids of the tag, the LIPID MAPS fatty acids are separated by
+    /// the separator
+    fn fatty_acids(&self, tag: &Tag, library: &Library, separator: &str) -> String {
+        match self.style {
+            Style::Abbreviation => tag.iter().join(""),
+            Style::Dash => tag.iter().join("-"),
+            Style::LipidMaps => tag
+                .iter()
+                .map(|fatty_acid| match library.fatty_acid(fatty_acid) {
+                    Some(structure) => format!("{}:{}", structure.carbons, structure.bonds),
+                    None => fatty_acid.clone(),
+                })
+                .join(separator),
+        }
+    }
+}
+
+/// Style of the notation
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Style {
+    /// Concatenated abbreviations (`POL`)
+    #[default]
+    Abbreviation,
+    /// Dash separated abbreviations (`P-O-L`)
+    Dash,
+    /// LIPID MAPS shorthand (`TG 16:0/18:1/18:2`), the fatty acids of the
+    /// unknown positions are separated by `_` (`TG 16:0_18:1_18:2`)
+    LipidMaps,
+}
+
+impl Style {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Abbreviation => "Abbreviations",
+            Self::Dash => "Dash",
+            Self::LipidMaps => "LIPID MAPS",
         }
-        true
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+/// Pattern of the tags, a wildcard per position, `None` (or a missing
+/// wildcard) matches any fatty acid
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Pattern {
-    fatty_acids: [Option<String>; 3],
+    wildcards: Vec<Option<Wildcard>>,
+    /// User-defined groups of the fatty acids
+    pub groups: IndexMap<String, BTreeSet<String>>,
+}
+
+impl Pattern {
+    /// Matcher of the tags, the classes of the fatty acids are taken from
+    /// the library
+    pub fn matcher<'a>(&'a self, library: &'a Library) -> Matcher<'a> {
+        Matcher {
+            pattern: self,
+            library,
+        }
+    }
+}
+
+impl Default for Pattern {
+    fn default() -> Self {
+        Self {
+            wildcards: vec![None; 3],
+            groups: IndexMap::new(),
+        }
+    }
 }
 
 impl Deref for Pattern {
-    type Target = [Option<String>; 3];
+    type Target = Vec<Option<Wildcard>>;
 
     fn deref(&self) -> &Self::Target {
-        &self.fatty_acids
+        &self.wildcards
     }
 }
 
 impl DerefMut for Pattern {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.fatty_acids
+        &mut self.wildcards
+    }
+}
+
+/// Pattern with the library
+#[derive(Clone, Copy, Debug)]
+pub struct Matcher<'a> {
+    pattern: &'a Pattern,
+    library: &'a Library,
+}
+
+impl Matcher<'_> {
+    pub fn library(&self) -> &Library {
+        self.library
+    }
+}
+
+/// Wildcard of the fatty acid position
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Wildcard {
+    FattyAcid(String),
+    /// Any fatty acid of the set
+    Set(BTreeSet<String>),
+    /// Any fatty acid of the class
+    Class(Class),
+    /// Any fatty acid of the user-defined group of the pattern
+    Group(String),
+    /// Any fatty acid not matched by the wildcard
+    Not(Box<Wildcard>),
+}
+
+impl Wildcard {
+    pub fn matches(&self, fatty_acid: &str, matcher: &Matcher) -> bool {
+        match self {
+            Self::FattyAcid(expected) => expected == fatty_acid,
+            Self::Set(set) => set.contains(fatty_acid),
+            Self::Class(class) => matcher.library.class(fatty_acid) == Some(*class),
+            Self::Group(group) => matcher
+                .pattern
+                .groups
+                .get(group)
+                .is_some_and(|group| group.contains(fatty_acid)),
+            Self::Not(wildcard) => !wildcard.matches(fatty_acid, matcher),
+        }
+    }
+}
+
+impl Display for Wildcard {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::FattyAcid(fatty_acid) => write!(f, "{fatty_acid}"),
+            Self::Set(set) => write!(f, "{{{}}}", set.iter().join(", ")),
+            Self::Class(class) => write!(f, "{}", class.abbreviation()),
+            Self::Group(group) => write!(f, "@{group}"),
+            Self::Not(wildcard) => write!(f, "¬{wildcard}"),
+        }
     }
 }
 
@@ -197,3 +631,111 @@ impl DerefMut for Pattern {
 //         Ok(())
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(layout: Layout, fatty_acids: &[&str]) -> Tag {
+        Tag::new(
+            layout,
+            fatty_acids
+                .iter()
+                .map(|&fatty_acid| fatty_acid.to_owned())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn parse(text: &str, layout: Layout) -> Result<Tag> {
+        let fatty_acids = ["P", "O", "L", "Ln", "St"].map(String::from);
+        Tag::parse(text, layout, &fatty_acids, &Library::default())
+    }
+
+    #[test]
+    fn abbreviations() {
+        let triacylglycerol = Layout::Triacylglycerol;
+        assert_eq!(
+            parse("POL", triacylglycerol).unwrap(),
+            tag(triacylglycerol, &["P", "O", "L"])
+        );
+        assert_eq!(
+            parse("LnLLn", triacylglycerol).unwrap(),
+            tag(triacylglycerol, &["Ln", "L", "Ln"])
+        );
+        assert_eq!(
+            parse("StOSt", triacylglycerol).unwrap(),
+            tag(triacylglycerol, &["St", "O", "St"])
+        );
+        assert_eq!(
+            parse("P-O-L", triacylglycerol).unwrap(),
+            tag(triacylglycerol, &["P", "O", "L"])
+        );
+        assert_eq!(
+            parse("18:2n-6-P-18:3ω-3", triacylglycerol).unwrap(),
+            tag(triacylglycerol, &["L", "P", "Ln"])
+        );
+        assert_eq!(
+            parse("P-18:1n-9-P", triacylglycerol).unwrap(),
+            tag(triacylglycerol, &["P", "O", "P"])
+        );
+        assert_eq!(
+            parse(" sn-POL ", triacylglycerol).unwrap(),
+            tag(triacylglycerol, &["P", "O", "L"])
+        );
+    }
+
+    #[test]
+    fn lipid_maps() {
+        let triacylglycerol = Layout::Triacylglycerol;
+        let expected = tag(triacylglycerol, &["P", "O", "L"]);
+        assert_eq!(
+            parse("TG 16:0/18:1/18:2", triacylglycerol).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse("TG sn-16:0/18:1/18:2", triacylglycerol).unwrap(),
+            expected
+        );
+        assert_eq!(parse("16:0_18:1_18:2", triacylglycerol).unwrap(), expected);
+        assert_eq!(
+            parse("TG 16:0/18:1(9Z)/18:3", triacylglycerol).unwrap(),
+            tag(triacylglycerol, &["P", "O", "Ln"]),
+        );
+        let diacylglycerol = Layout::Diacylglycerol;
+        assert_eq!(
+            parse("DG 16:0/18:1", diacylglycerol).unwrap(),
+            tag(diacylglycerol, &["P", "O"])
+        );
+    }
+
+    #[test]
+    fn sum() {
+        let library = Library::default();
+        let triacylglycerol = Layout::Triacylglycerol;
+        assert_eq!(
+            tag(triacylglycerol, &["P", "O", "L"]).sum(&library),
+            Some((52, 3))
+        );
+        assert_eq!(
+            tag(triacylglycerol, &["120:0", "120:0", "P"]).sum(&library),
+            None
+        );
+        assert_eq!(tag(triacylglycerol, &["P", "X", "L"]).sum(&library), None);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse("PO", Layout::Triacylglycerol).is_err());
+        assert!(parse("POL", Layout::Diacylglycerol).is_err());
+        assert!(parse("PXL", Layout::Triacylglycerol).is_err());
+        assert!(parse("TG 16:0/18:1", Layout::Triacylglycerol).is_err());
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(
+            parse("P-X-L", Layout::Triacylglycerol).unwrap(),
+            tag(Layout::Triacylglycerol, &["P", "X", "L"]),
+        );
+    }
+}
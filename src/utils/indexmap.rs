@@ -5,16 +5,16 @@ use std::hash::Hash;
 pub(crate) trait IndexMapExt<K, V> {
     fn replace<T>(&mut self, from: &T, to: K) -> Option<usize>
     where
-        T: Hash + Equivalent<K>;
+        T: ?Sized + Hash + Equivalent<K>;
 }
 
 impl<K, V> IndexMapExt<K, V> for IndexMap<K, V>
 where
     K: Hash + Eq,
 {
-    fn replace<T: ?Sized>(&mut self, from: &T, to: K) -> Option<usize>
+    fn replace<T>(&mut self, from: &T, to: K) -> Option<usize>
     where
-        T: Hash + Equivalent<K>,
+        T: ?Sized + Hash + Equivalent<K>,
     {
         let (b, _, value) = self.swap_remove_full(from)?;
         let (a, _) = self.insert_full(to, value);
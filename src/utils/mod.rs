@@ -5,9 +5,13 @@ pub(crate) use self::{
 pub(crate) use bound::BoundExt;
 pub(crate) use float::FloatExt;
 pub(crate) use info::Info;
+pub(crate) use solver::minimize;
+pub(crate) use task::Task;
 
 mod bound;
 mod egui;
 mod float;
 mod indexmap;
 mod info;
+mod solver;
+mod task;
@@ -0,0 +1,60 @@
+/// Maximal number of the solver iterations
+const ITERATIONS: usize = 500;
+
+/// Step of the forward differences
+const STEP: f64 = 1e-6;
+
+/// Convergence tolerance of the parameters
+const TOLERANCE: f64 = 1e-9;
+
+/// Projected gradient descent with the forward-difference gradient and the
+/// backtracking step. The start must be feasible, the candidates are projected
+/// onto the feasible set. Returns the parameters and the value of the function.
+pub(crate) fn minimize(
+    function: impl Fn(&[f64]) -> f64,
+    project: impl Fn(&mut [f64]),
+    mut parameters: Vec<f64>,
+) -> (Vec<f64>, f64) {
+    let mut value = function(&parameters);
+    let mut step = 1.0;
+    for _ in 0..ITERATIONS {
+        let gradient = (0..parameters.len())
+            .map(|index| {
+                let mut perturbed = parameters.clone();
+                perturbed[index] += STEP;
+                (function(&perturbed) - value) / STEP
+            })
+            .collect::<Vec<_>>();
+        let improved = loop {
+            let mut candidate = parameters
+                .iter()
+                .zip(&gradient)
+                .map(|(parameter, gradient)| parameter - step * gradient)
+                .collect::<Vec<_>>();
+            project(&mut candidate);
+            let candidate_value = function(&candidate);
+            if candidate_value < value {
+                break Some((candidate, candidate_value));
+            }
+            step /= 2.0;
+            if step < f64::EPSILON {
+                break None;
+            }
+        };
+        let (candidate, candidate_value) = match improved {
+            Some(improved) => improved,
+            None => break,
+        };
+        let converged = parameters
+            .iter()
+            .zip(&candidate)
+            .all(|(parameter, candidate)| (parameter - candidate).abs() < TOLERANCE);
+        parameters = candidate;
+        value = candidate_value;
+        step *= 2.0;
+        if converged {
+            break;
+        }
+    }
+    (parameters, value)
+}
@@ -0,0 +1,42 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fmt::{self, Debug, Formatter},
+    rc::Rc,
+    sync::mpsc::{channel, Receiver, TryRecvError},
+};
+
+/// Computation off the UI thread: run on a worker thread on the native target,
+/// in place on the web without the threads
+pub(crate) struct Task<T>(Rc<Receiver<T>>);
+
+impl<T: Send + 'static> Task<T> {
+    pub(crate) fn spawn(f: impl FnOnce() -> T + Send + 'static) -> Self {
+        let (sender, receiver) = channel();
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(move || sender.send(f()).ok());
+        #[cfg(target_arch = "wasm32")]
+        sender.send(f()).ok();
+        Self(Rc::new(receiver))
+    }
+
+    /// Result of the computation, `None` while running
+    pub(crate) fn poll(&self) -> Option<Result<T>> {
+        match self.0.try_recv() {
+            Ok(value) => Some(Ok(value)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!("Task failed"))),
+        }
+    }
+}
+
+impl<T> Clone for Task<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Debug for Task<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Task").finish()
+    }
+}
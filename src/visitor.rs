@@ -1,46 +1,221 @@
-use crate::Specie;
+use crate::{
+    fatty_acid::{Entry, Library},
+    input::Layout,
+    Input, Specie,
+};
 use anyhow::Error;
 use indexmap::IndexMap;
 use inflector::Inflector;
-use toml_edit::{visit::*, Document, Item};
+use toml_edit::{visit::*, Document, Item, TableLike};
+use tracing::error;
 
-/// Collect the names of every dependency key.
+/// Collect the fatty acid values of every specie.
+///
+/// Two layouts are supported: an array of positional values per fatty acid
+/// (`P = [sn13, sn2, sn123]` or `P = [sn13, sn2, sn123, sn1, sn3]` for
+/// stereospecific input) and an array of tables per specie with `key` and
+/// `value = { sn13, sn2, sn123, sn1, sn3 }` entries. The optional `shorthand`,
+/// `name`, `systematic_name` and `type` entries of the table layout extend or
+/// override the fatty acid library.
+///
+/// Standard deviations are optional: `sd = { sn13, sn2, sn123, sn1, sn3 }` in
+/// the table layout and `{ value, sd }` elements in the array layout
+/// (`P = [{ value = 40.0, sd = 0.5 }, 0.0, 28.1]`).
+///
+/// The diacylglycerol and phospholipid values (`sn1`, `sn2`, `sn12`) take
+/// place of the triacylglycerol ones with the top-level `layout = "DAG"` (or
+/// `"PL"`) key.
+///
+/// Replicates are an array of value arrays in the array layout
+/// (`P = [[40.0, 0.0, 28.1], [39.6, 0.1, 27.8]]`) and an array of value tables
+/// in the table layout (`value = [{ sn13, sn2, sn123 }, { sn13, sn2, sn123 }]`).
+/// The values of the specie are the means of the replicates, a fatty acid
+/// without replicates is the same in every replicate.
 #[derive(Debug, Default)]
 pub struct Visitor<'a> {
     taxonomy: Vec<&'a str>,
     result: IndexMap<Specie, IndexMap<String, Vec<f64>>>,
+    deviations: IndexMap<Specie, IndexMap<String, Vec<f64>>>,
+    replicates: IndexMap<Specie, IndexMap<String, Vec<Vec<f64>>>>,
+    layout: Layout,
+    library: Library,
     errors: Vec<Error>,
 }
 
 impl Visitor<'_> {
-    pub fn visit(document: &Document) -> IndexMap<Specie, IndexMap<String, Vec<f64>>> {
+    pub fn visit(document: &Document) -> Input {
         let mut visitor = Visitor::default();
+        if let Some(layout) = document.get("layout").and_then(Item::as_str) {
+            match layout.parse() {
+                Ok(layout) => visitor.layout = layout,
+                Err(error) => error!(%error),
+            }
+        }
         visitor.visit_document(document);
-        visitor.result
+        let replicates = visitor
+            .replicates
+            .into_iter()
+            .map(|(specie, replicated)| {
+                let fatty_acids = &visitor.result[&specie];
+                let count = replicated.values().map(Vec::len).max().unwrap_or_default();
+                let replicates = (0..count)
+                    .map(|index| {
+                        fatty_acids
+                            .iter()
+                            .map(|(key, values)| {
+                                let values = replicated
+                                    .get(key)
+                                    .and_then(|replicates| replicates.get(index))
+                                    .unwrap_or(values);
+                                (key.clone(), values.clone())
+                            })
+                            .collect()
+                    })
+                    .collect();
+                (specie, replicates)
+            })
+            .collect();
+        Input::new(
+            visitor.result,
+            visitor.deviations,
+            replicates,
+            visitor.layout,
+            visitor.library,
+        )
+    }
+}
+
+impl<'a> Visitor<'a> {
+    fn specie(&self) -> Specie {
+        self.taxonomy
+            .iter()
+            .copied()
+            .map(Inflector::to_title_case)
+            .collect()
+    }
+
+    /// Insert the fatty acid values of the current specie, the mean of the
+    /// replicates if there are several
+    fn insert(&mut self, key: &str, mut replicates: Vec<Vec<f64>>) {
+        let specie = self.specie();
+        let value = if replicates.len() == 1 {
+            replicates.remove(0)
+        } else {
+            let count = replicates.len() as f64;
+            let value = (0..replicates.iter().map(Vec::len).min().unwrap_or_default())
+                .map(|index| {
+                    replicates
+                        .iter()
+                        .map(|replicate| replicate[index])
+                        .sum::<f64>()
+                        / count
+                })
+                .collect();
+            self.replicates
+                .entry(specie.clone())
+                .or_default()
+                .insert(key.to_string(), replicates);
+            value
+        };
+        self.result
+            .entry(specie)
+            .or_default()
+            .insert(key.to_string(), value);
     }
 }
 
 impl<'a> Visit<'a> for Visitor<'a> {
     fn visit_table_like_kv(&mut self, key: &'a str, node: &'a Item) {
-        if let Some(array) = node.as_array() {
-            let value = array
+        if let Some(array_of_tables) = node.as_array_of_tables() {
+            self.taxonomy.push(key);
+            let specie = self.specie();
+            for table in array_of_tables.iter() {
+                let key = table
+                    .get("key")
+                    .and_then(Item::as_str)
+                    .unwrap_or_else(|| panic!("Parse key ({table}) as string"));
+                let value = table
+                    .get("value")
+                    .unwrap_or_else(|| panic!("Parse value ({table}) as table"));
+                let replicates = match value.as_array() {
+                    Some(array) => array
+                        .iter()
+                        .map(|value| {
+                            values(
+                                value
+                                    .as_inline_table()
+                                    .unwrap_or_else(|| panic!("Parse value ({value}) as table")),
+                                self.layout,
+                            )
+                        })
+                        .collect(),
+                    None => vec![values(
+                        value
+                            .as_table_like()
+                            .unwrap_or_else(|| panic!("Parse value ({table}) as table")),
+                        self.layout,
+                    )],
+                };
+                let entry = Entry {
+                    shorthand: string(table, "shorthand"),
+                    name: string(table, "name"),
+                    systematic_name: string(table, "systematic_name"),
+                    saturation: string(table, "type").and_then(|r#type| {
+                        r#type.parse().map_err(|error: Error| error!(%error)).ok()
+                    }),
+                };
+                if !entry.is_empty() {
+                    self.library.insert(key.to_string(), entry);
+                }
+                if let Some(deviations) = table.get("sd").and_then(Item::as_table_like) {
+                    self.deviations
+                        .entry(specie.clone())
+                        .or_default()
+                        .insert(key.to_string(), values(deviations, self.layout));
+                }
+                self.insert(key, replicates);
+            }
+            self.taxonomy.pop();
+        } else if let Some(array) = node.as_array() {
+            if !array.is_empty() && array.iter().all(|value| value.is_array()) {
+                let replicates = array
+                    .iter()
+                    .map(|value| {
+                        value
+                            .as_array()
+                            .unwrap_or_else(|| panic!("Parse value ({value}) as array"))
+                            .iter()
+                            .map(|value| {
+                                value
+                                    .as_float()
+                                    .unwrap_or_else(|| panic!("Parse value ({value}) as float"))
+                            })
+                            .collect()
+                    })
+                    .collect();
+                self.insert(key, replicates);
+                return;
+            }
+            let (value, deviations): (Vec<_>, Vec<_>) = array
                 .iter()
-                .map(|value| {
-                    value
-                        .as_float()
-                        .unwrap_or_else(|| panic!("Parse value ({value}) as float"))
+                .map(|value| match value.as_inline_table() {
+                    Some(table) => (float(table, "value"), float(table, "sd")),
+                    None => (
+                        value
+                            .as_float()
+                            .unwrap_or_else(|| panic!("Parse value ({value}) as float")),
+                        0.0,
+                    ),
                 })
-                .collect();
-            let specie = self
-                .taxonomy
-                .iter()
-                .copied()
-                .map(Inflector::to_title_case)
-                .collect();
-            self.result
-                .entry(specie)
-                .or_default()
-                .insert(key.to_string(), value);
+                .unzip();
+            let specie = self.specie();
+            if deviations.iter().any(|&deviation| deviation != 0.0) {
+                self.deviations
+                    .entry(specie.clone())
+                    .or_default()
+                    .insert(key.to_string(), deviations);
+            }
+            self.insert(key, vec![value]);
         } else {
             self.taxonomy.push(key);
             self.visit_item(node);
@@ -48,3 +223,27 @@ impl<'a> Visit<'a> for Visitor<'a> {
         }
     }
 }
+
+/// Positional values of the table layout, the stereospecific sn-1 and sn-3
+/// values are kept only if both are present
+fn values(table: &dyn TableLike, layout: Layout) -> Vec<f64> {
+    let keys = layout.keys();
+    let stereospecific = keys.len() > 3 && keys[3..].iter().all(|&key| table.contains_key(key));
+    let count = if stereospecific { keys.len() } else { 3 };
+    keys[..count].iter().map(|&key| float(table, key)).collect()
+}
+
+/// Float entry of the table, zero if absent
+fn float(table: &dyn TableLike, key: &str) -> f64 {
+    table.get(key).map_or(0.0, |value| {
+        value
+            .as_float()
+            .unwrap_or_else(|| panic!("Parse value ({value}) as float"))
+    })
+}
+
+/// Non-empty string entry of the table
+fn string(table: &dyn TableLike, key: &str) -> Option<String> {
+    let value = table.get(key)?.as_str()?;
+    (!value.is_empty()).then(|| value.to_owned())
+}